@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+/// Stand-in for Jupiter V6 on the local validator, so router tests can run
+/// swaps end to end. `route` keeps Jupiter's instruction and account layout,
+/// takes `in_amount` from the user and pays exactly `quoted_out_amount` out
+/// of a pool owned by the `["pool_authority"]` PDA. The route plan, slippage
+/// and platform fee are ignored.
+#[program]
+pub mod mock_jupiter {
+    use super::*;
+
+    pub fn route(
+        ctx: Context<Route>,
+        _route_plan: Vec<RoutePlanStep>,
+        in_amount: u64,
+        quoted_out_amount: u64,
+        _slippage_bps: u16,
+        _platform_fee_bps: u8,
+    ) -> Result<()> {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_source_token_account.to_account_info(),
+                    mint: ctx.accounts.source_mint.to_account_info(),
+                    to: ctx.accounts.pool_source.to_account_info(),
+                    authority: ctx.accounts.user_transfer_authority.to_account_info(),
+                },
+            ),
+            in_amount,
+            ctx.accounts.source_mint.decimals,
+        )?;
+
+        let seeds = &[b"pool_authority".as_ref(), &[ctx.bumps.pool_authority]];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.destination_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.pool_destination.to_account_info(),
+                    mint: ctx.accounts.destination_mint.to_account_info(),
+                    to: ctx.accounts.user_destination_token_account.to_account_info(),
+                    authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            quoted_out_amount,
+            ctx.accounts.destination_mint.decimals,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Jupiter route plan step with a data-less swap variant
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RoutePlanStep {
+    pub swap: u8,
+    pub percent: u8,
+    pub input_index: u8,
+    pub output_index: u8,
+}
+
+// The first nine accounts follow Jupiter's `route`; the pool comes after
+#[derive(Accounts)]
+pub struct Route<'info> {
+    /// Program owning the source mint
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub user_transfer_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub user_source_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user_destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Optional destination override, unused
+    pub destination_token_account: UncheckedAccount<'info>,
+
+    pub destination_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Platform fee account, unused
+    pub platform_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Event authority, unused
+    pub event_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Jupiter program account, unused
+    pub program: UncheckedAccount<'info>,
+
+    pub source_mint: InterfaceAccount<'info, Mint>,
+
+    /// Receives the input
+    #[account(mut)]
+    pub pool_source: InterfaceAccount<'info, TokenAccount>,
+
+    /// Pays the output, owned by pool_authority
+    #[account(mut)]
+    pub pool_destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA signing for pool_destination
+    #[account(seeds = [b"pool_authority"], bump)]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    /// Program owning the destination mint
+    pub destination_token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::errors::ErrorCode;
+use crate::router::{RouterState, JUPITER_V6};
+
+/// Dollar-cost-averaging schedule executed by keepers
+/// (seeds: ["dca", owner, dca_id])
+/// Like limit orders, the schedule PDA owns its escrows and signs the
+/// Jupiter CPI for its own input only.
+#[account]
+pub struct DcaSchedule {
+    pub owner: Pubkey,                  // 32
+    pub dca_id: u64,                    // 8  (chosen by the owner)
+    pub input_mint: Pubkey,             // 32
+    pub output_mint: Pubkey,            // 32
+    pub destination: Pubkey,            // 32 (owner token account credited each cycle)
+    pub total_amount: u64,              // 8  (escrowed, net of any transfer fee)
+    pub amount_per_cycle: u64,          // 8
+    pub interval_secs: i64,             // 8
+    pub min_output_per_cycle: u64,      // 8  (price floor for a full cycle, > 0)
+    pub next_cycle_ts: i64,             // 8  (earliest unix time for the next cycle)
+    pub cycles_executed: u32,           // 4
+    pub total_spent: u64,               // 8
+    pub total_received: u64,            // 8  (credited to the owner)
+    pub keeper_bounty_bps: u16,         // 2  (router bounty when created)
+    pub bump: u8,                       // 1
+}
+
+impl DcaSchedule {
+    pub const INIT_SPACE: usize = 32 + 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 4 + 8 + 8 + 2 + 1;
+
+    /// Floor for a cycle selling `amount_in`, pro rata for a short last cycle
+    /// (rounded up so it never drops to zero)
+    pub fn min_output_for(&self, amount_in: u64) -> Result<u64> {
+        let floor = (self.min_output_per_cycle as u128)
+            .checked_mul(amount_in as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .div_ceil(self.amount_per_cycle as u128);
+        Ok(floor as u64)
+    }
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[derive(Accounts)]
+#[instruction(dca_id: u64)]
+pub struct CreateDca<'info> {
+    #[account(seeds = [b"router_state"], bump = router_state.bump)]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DcaSchedule::INIT_SPACE,
+        seeds = [b"dca", owner.key().as_ref(), dca_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub dca: Account<'info, DcaSchedule>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"dca_escrow", dca.key().as_ref()],
+        bump,
+        token::mint = input_mint,
+        token::authority = dca,
+        token::token_program = input_token_program
+    )]
+    pub input_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives each cycle's Jupiter output before it is split
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"dca_output", dca.key().as_ref()],
+        bump,
+        token::mint = output_mint,
+        token::authority = dca,
+        token::token_program = output_token_program
+    )]
+    pub output_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = input_mint, token::authority = owner)]
+    pub owner_source_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(token::mint = output_mint, token::authority = owner)]
+    pub owner_destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(address = *input_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub input_token_program: Interface<'info, TokenInterface>,
+    #[account(address = *output_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub output_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteDcaCycle<'info> {
+    #[account(mut, seeds = [b"router_state"], bump = router_state.bump)]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        mut,
+        seeds = [b"dca", dca.owner.as_ref(), dca.dca_id.to_le_bytes().as_ref()],
+        bump = dca.bump
+    )]
+    pub dca: Account<'info, DcaSchedule>,
+
+    #[account(mut, seeds = [b"dca_escrow", dca.key().as_ref()], bump)]
+    pub input_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"dca_output", dca.key().as_ref()], bump)]
+    pub output_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = dca.input_mint @ ErrorCode::TokenMintMismatch)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = dca.output_mint @ ErrorCode::TokenMintMismatch)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, address = dca.destination @ ErrorCode::InvalidOrderDestination)]
+    pub owner_destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", output_mint.key().as_ref()],
+        bump
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub keeper: Signer<'info>,
+
+    /// Keeper account receiving the bounty in the output mint
+    #[account(mut, token::mint = output_mint)]
+    pub keeper_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Jupiter V6 Program
+    #[account(constraint = jupiter_program.key() == JUPITER_V6 @ ErrorCode::InvalidJupiterProgram)]
+    pub jupiter_program: AccountInfo<'info>,
+
+    #[account(address = *output_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Price feed PDAs and oracles for both mints, as on SwapAccounts;
+    /// the feeds are required while the oracle guard is on
+    #[account(seeds = [b"price_feed", dca.input_mint.as_ref()], bump)]
+    pub input_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Oracle named by input_price_feed, validated when read
+    pub input_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: See input_price_feed
+    #[account(seeds = [b"price_feed", dca.output_mint.as_ref()], bump)]
+    pub output_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Oracle named by output_price_feed, validated when read
+    pub output_oracle: Option<UncheckedAccount<'info>>,
+
+    // Jupiter route accounts are passed via remaining_accounts, with
+    // `input_escrow` as the source, `output_escrow` as the destination and
+    // `dca` as the transfer authority
+}
+
+#[derive(Accounts)]
+pub struct CloseDca<'info> {
+    #[account(
+        mut,
+        seeds = [b"dca", owner.key().as_ref(), dca.dca_id.to_le_bytes().as_ref()],
+        bump = dca.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub dca: Account<'info, DcaSchedule>,
+
+    #[account(mut, seeds = [b"dca_escrow", dca.key().as_ref()], bump)]
+    pub input_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"dca_output", dca.key().as_ref()], bump)]
+    pub output_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Mints are writable to receive fees withheld in the escrows
+    #[account(mut, address = dca.input_mint @ ErrorCode::TokenMintMismatch)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, address = dca.output_mint @ ErrorCode::TokenMintMismatch)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    /// Receives the unspent input
+    #[account(mut, token::mint = input_mint, token::authority = owner)]
+    pub owner_source_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives anything left in the output escrow so it can be closed
+    #[account(mut, address = dca.destination @ ErrorCode::InvalidOrderDestination)]
+    pub owner_destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(address = *input_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub input_token_program: Interface<'info, TokenInterface>,
+    #[account(address = *output_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub output_token_program: Interface<'info, TokenInterface>,
+}
@@ -46,4 +46,103 @@ pub enum ErrorCode {
     
     #[msg("Token mint mismatch")]
     TokenMintMismatch,
-}
\ No newline at end of file
+    
+    #[msg("Invalid Jupiter program provided")]
+    InvalidJupiterProgram,
+    
+    #[msg("Empty instruction data")]
+    EmptyInstructionData,
+    
+    #[msg("Router is paused")]
+    RouterPaused,
+    
+    #[msg("Vault program is not registered with the router")]
+    VaultProgramNotAllowed,
+    
+    #[msg("Vault authority is not a PDA of the vault program")]
+    InvalidVaultAuthority,
+    
+    #[msg("Vault program allow-list is full")]
+    VaultProgramListFull,
+    
+    #[msg("DEX registry is full")]
+    DexRegistryFull,
+    
+    #[msg("Route hop spent an unexpected amount")]
+    RouteHopAmountMismatch,
+    
+    #[msg("Unsupported Jupiter instruction")]
+    UnsupportedJupiterInstruction,
+    
+    #[msg("Jupiter instruction accounts do not match the swap accounts")]
+    JupiterAccountMismatch,
+    
+    #[msg("Slippage tolerance above the router limit")]
+    SlippageToleranceTooHigh,
+    
+    #[msg("Output delta does not match the exact-out target")]
+    ExactOutputMismatch,
+    
+    #[msg("Invalid partner revenue share")]
+    InvalidRevenueShare,
+    
+    #[msg("Partner is not active")]
+    PartnerInactive,
+    
+    #[msg("Partner fee account does not belong to the partner or output mint")]
+    InvalidPartnerFeeAccount,
+    
+    #[msg("Fee tiers must have increasing non-zero minimum volumes")]
+    InvalidFeeTiers,
+    
+    #[msg("Mint uses a Token-2022 extension the router does not support")]
+    UnsupportedMintExtension,
+    
+    #[msg("Token program does not own the account")]
+    InvalidTokenProgram,
+    
+    #[msg("Oracle account does not match the registered price feed")]
+    InvalidOracleAccount,
+    
+    #[msg("Oracle price is stale")]
+    StaleOraclePrice,
+    
+    #[msg("Execution price deviates too far from the oracle price")]
+    OraclePriceDeviation,
+    
+    #[msg("Invalid oracle configuration")]
+    InvalidOracleConfig,
+    
+    #[msg("Swap expired: past valid_until_slot or deadline")]
+    SwapExpired,
+    
+    #[msg("Order expired")]
+    OrderExpired,
+    
+    #[msg("Token account is not the order's destination")]
+    InvalidOrderDestination,
+    
+    #[msg("Keeper bounty above the router limit")]
+    InvalidKeeperBounty,
+    
+    #[msg("Invalid DCA schedule")]
+    InvalidDcaSchedule,
+    
+    #[msg("DCA cycle not due yet")]
+    DcaCycleNotDue,
+    
+    #[msg("DCA schedule fully executed")]
+    DcaCompleted,
+    
+    #[msg("Arbitrage profit below the minimum")]
+    InsufficientProfit,
+    
+    #[msg("Volume mint allow-list is full")]
+    VolumeMintListFull,
+    
+    #[msg("Price feed accounts are required while the oracle guard is on")]
+    MissingPriceFeed,
+    
+    #[msg("Jupiter platform fee not allowed")]
+    PlatformFeeNotAllowed,
+}
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use crate::oracle::OracleKind;
+use crate::registry::AdapterKind;
+use crate::user_stats::FeeTier;
+
+/// Which execution path produced a swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteKind {
+    Jupiter,
+    Dex(AdapterKind),
+    NativePool,
+    MultiHop { hops: u8 },
+    JupiterExactOut,
+    /// Round trip; `amount_out` is the profit
+    Arbitrage { hops: u8 },
+}
+
+/// One user swap settled by the router
+#[event]
+pub struct SwapExecuted {
+    pub user: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,  // before the router fee
+    pub fee_amount: u64,
+    pub route_kind: RouteKind,
+    pub slot: u64,
+}
+
+/// One swap executed for a registered vault program (no router fee)
+/// For round trips through a single token account only the net change is
+/// observable: it shows up as amount_in (loss) or amount_out (gain).
+#[event]
+pub struct VaultSwapExecuted {
+    pub vault_authority: Pubkey,
+    pub vault_program: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub route_kind: RouteKind,
+    pub slot: u64,
+}
+
+#[event]
+pub struct FeeCollected {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+#[event]
+pub struct FeeRateUpdated {
+    pub old_fee_rate_bps: u16,
+    pub new_fee_rate_bps: u16,
+}
+
+#[event]
+pub struct MaxSlippageUpdated {
+    pub old_max_slippage_bps: u16,
+    pub new_max_slippage_bps: u16,
+}
+
+#[event]
+pub struct FeeTiersUpdated {
+    pub tiers: Vec<FeeTier>,
+}
+
+#[event]
+pub struct KeeperBountyUpdated {
+    pub old_keeper_bounty_bps: u16,
+    pub new_keeper_bounty_bps: u16,
+}
+
+#[event]
+pub struct OracleGuardUpdated {
+    pub max_price_deviation_bps: u16,
+    pub max_oracle_staleness_secs: u32,
+}
+
+#[event]
+pub struct PriceFeedUpdated {
+    pub mint: Pubkey,
+    pub kind: OracleKind,
+    pub oracle: Pubkey,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub paused: bool,
+}
+
+#[event]
+pub struct VaultProgramUpdated {
+    pub program_id: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct VolumeMintUpdated {
+    pub mint: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct DexProgramUpdated {
+    pub program_id: Pubkey,
+    pub kind: Option<AdapterKind>, // None = removed
+}
+
+#[event]
+pub struct PoolCreated {
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub lp_mint: Pubkey,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_amount: u64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_amount: u64,
+}
+
+#[event]
+pub struct PartnerUpdated {
+    pub partner: Pubkey,
+    pub authority: Pubkey,
+    pub revenue_share_bps: u16,
+    pub active: bool,
+}
+
+#[event]
+pub struct PartnerFeeAccrued {
+    pub partner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PartnerFeesClaimed {
+    pub partner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LimitOrderPlaced {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub input_amount: u64,
+    pub min_output_amount: u64,
+    pub expiry_unix_ts: i64,
+}
+
+#[event]
+pub struct LimitOrderCancelled {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct LimitOrderFilled {
+    pub order: Pubkey,
+    pub owner: Pubkey,
+    pub keeper: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,     // before the router fee and keeper bounty
+    pub fee_amount: u64,
+    pub keeper_bounty: u64,
+}
+
+#[event]
+pub struct DcaCreated {
+    pub dca: Pubkey,
+    pub owner: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub total_amount: u64,
+    pub amount_per_cycle: u64,
+    pub interval_secs: i64,
+    pub min_output_per_cycle: u64,
+}
+
+#[event]
+pub struct DcaCycleExecuted {
+    pub dca: Pubkey,
+    pub owner: Pubkey,
+    pub keeper: Pubkey,
+    pub cycle: u32,
+    pub amount_in: u64,
+    pub amount_out: u64,     // before the router fee and keeper bounty
+    pub fee_amount: u64,
+    pub keeper_bounty: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct DcaClosed {
+    pub dca: Pubkey,
+    pub owner: Pubkey,
+    pub refunded: u64,
+    pub cycles_executed: u32,
+}
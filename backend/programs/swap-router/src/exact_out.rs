@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+use crate::errors::ErrorCode;
+use crate::router::JUPITER_V6;
+use crate::swap::*;
+use crate::tokens::*;
+
+/// Accounts for an exact-out Jupiter swap.
+/// The user's max input is parked in a per-user escrow owned by
+/// `escrow_authority`, Jupiter spends from the escrow, and whatever is left
+/// goes back to the user before the escrow is closed.
+#[derive(Accounts)]
+pub struct ExecuteExactOutSwap<'info> {
+    pub swap: SwapAccounts<'info>,
+
+    /// Writable to receive transfer fees withheld in the escrow
+    #[account(mut, constraint = source_mint.key() == swap.user_source_token.mint @ ErrorCode::TokenMintMismatch)]
+    pub source_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: PDA that only ever owns exact-out escrows; signs the Jupiter CPI
+    #[account(seeds = [b"escrow_authority"], bump)]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// Intermediate router-owned account holding the max input for this swap
+    #[account(
+        init,
+        payer = swap.user,
+        seeds = [b"exact_out_escrow", swap.user.key().as_ref(), source_mint.key().as_ref()],
+        bump,
+        token::mint = source_mint,
+        token::authority = escrow_authority,
+        token::token_program = source_token_program
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Jupiter V6 Program
+    #[account(constraint = jupiter_program.key() == JUPITER_V6)]
+    pub jupiter_program: AccountInfo<'info>,
+
+    // Program owning source_mint (one of the swap token programs);
+    // init constraints need a top-level field
+    #[account(address = *source_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub source_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    // Jupiter exact_out_route accounts are passed via remaining_accounts,
+    // with `escrow` as the source and `escrow_authority` as the transfer authority
+}
+
+impl<'info> ExecuteExactOutSwap<'info> {
+    /// Move the max input from the user into the escrow
+    pub fn fund_escrow(&self, amount: u64) -> Result<()> {
+        transfer_tokens(
+            &self.swap.user_source_token.to_account_info(),
+            &self.source_mint.to_account_info(),
+            &self.escrow.to_account_info(),
+            &self.swap.user.to_account_info(),
+            &[&self.source_token_program.to_account_info()],
+            amount,
+            &[],
+        )
+    }
+
+    /// Return unused input to the user and close the escrow (rent to the user),
+    /// harvesting any withheld transfer fees to the mint first.
+    /// Returns the refunded amount.
+    pub fn refund_and_close(&mut self, escrow_authority_bump: u8) -> Result<u64> {
+        self.escrow.reload()?;
+        let refund = self.escrow.amount;
+
+        let seeds = &[b"escrow_authority".as_ref(), &[escrow_authority_bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        if refund > 0 {
+            transfer_tokens(
+                &self.escrow.to_account_info(),
+                &self.source_mint.to_account_info(),
+                &self.swap.user_source_token.to_account_info(),
+                &self.escrow_authority.to_account_info(),
+                &[&self.source_token_program.to_account_info()],
+                refund,
+                signer_seeds,
+            )?;
+        }
+
+        harvest_withheld_fees(
+            &self.escrow.to_account_info(),
+            &self.source_mint.to_account_info(),
+            &self.source_token_program.to_account_info(),
+        )?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            self.source_token_program.to_account_info(),
+            CloseAccount {
+                account: self.escrow.to_account_info(),
+                destination: self.swap.user.to_account_info(),
+                authority: self.escrow_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        Ok(refund)
+    }
+}
+
+/// Like `passthrough_instruction`, but marks `signer` as a signer so the
+/// router can sign for it with invoke_signed
+pub fn passthrough_instruction_with_signer(
+    program_id: Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+    signer: &Pubkey,
+) -> anchor_lang::solana_program::instruction::Instruction {
+    let mut ix = passthrough_instruction(program_id, accounts, data);
+    for meta in ix.accounts.iter_mut().filter(|m| m.pubkey == *signer) {
+        meta.is_signer = true;
+    }
+    ix
+}
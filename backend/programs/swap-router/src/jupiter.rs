@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::utils::*;
+
+// Anchor discriminators of the Jupiter V6 swap instructions
+pub const ROUTE_DISCRIMINATOR: [u8; 8] = [229, 23, 203, 151, 122, 227, 173, 42];
+pub const SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR: [u8; 8] = [193, 32, 155, 51, 65, 214, 156, 129];
+pub const EXACT_OUT_ROUTE_DISCRIMINATOR: [u8; 8] = [208, 51, 239, 151, 123, 43, 237, 92];
+
+// Every supported instruction ends with two u64 amounts, slippage_bps: u16
+// and platform_fee_bps: u8, right after the variable-length route plan
+const ARGS_TAIL_LEN: usize = 8 + 8 + 2 + 1;
+
+/// Supported Jupiter V6 swap instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JupiterInstructionKind {
+    Route,
+    SharedAccountsRoute,
+    ExactOutRoute,
+}
+
+/// Positions of the policy-relevant accounts in a Jupiter instruction
+struct JupiterAccountLayout {
+    source_token: usize,
+    destination_token: usize,
+    // Optional destination override (Jupiter program id when unused)
+    destination_override: Option<usize>,
+    source_mint: Option<usize>,
+    destination_mint: usize,
+}
+
+impl JupiterInstructionKind {
+    fn account_layout(&self) -> JupiterAccountLayout {
+        match self {
+            // token_program, user_transfer_authority, user_source_token_account,
+            // user_destination_token_account, destination_token_account, destination_mint, ...
+            JupiterInstructionKind::Route => JupiterAccountLayout {
+                source_token: 2,
+                destination_token: 3,
+                destination_override: Some(4),
+                source_mint: None,
+                destination_mint: 5,
+            },
+            // token_program, program_authority, user_transfer_authority, source_token_account,
+            // program_source_token_account, program_destination_token_account,
+            // destination_token_account, source_mint, destination_mint, ...
+            JupiterInstructionKind::SharedAccountsRoute => JupiterAccountLayout {
+                source_token: 3,
+                destination_token: 6,
+                destination_override: None,
+                source_mint: Some(7),
+                destination_mint: 8,
+            },
+            // token_program, user_transfer_authority, user_source_token_account,
+            // user_destination_token_account, destination_token_account, source_mint,
+            // destination_mint, ...
+            JupiterInstructionKind::ExactOutRoute => JupiterAccountLayout {
+                source_token: 2,
+                destination_token: 3,
+                destination_override: Some(4),
+                source_mint: Some(5),
+                destination_mint: 6,
+            },
+        }
+    }
+}
+
+/// Amounts and tolerances decoded from a Jupiter V6 instruction.
+/// For `route` / `shared_accounts_route` the input is exact and the output
+/// quoted; for `exact_out_route` the output is exact and the input quoted.
+#[derive(Clone, Copy, Debug)]
+pub struct JupiterSwapParams {
+    pub kind: JupiterInstructionKind,
+    pub in_amount: u64,
+    pub quoted_out_amount: u64,
+    pub slippage_bps: u16,
+    pub platform_fee_bps: u8,
+}
+
+/// Token accounts the router expects a Jupiter instruction to trade between
+pub struct ExpectedSwapAccounts {
+    pub source_token: Pubkey,
+    pub source_mint: Pubkey,
+    pub destination_token: Pubkey,
+    pub destination_mint: Pubkey,
+}
+
+/// Router-side limits applied before forwarding to Jupiter
+pub struct JupiterPolicy {
+    /// 0 = no router limit
+    pub max_slippage_bps: u16,
+    pub max_amount_in: Option<u64>,
+    /// Whether the payload may charge a Jupiter platform fee
+    pub allow_platform_fee: bool,
+}
+
+/// Decode a Jupiter V6 instruction payload.
+/// The route plan is skipped; only the fixed arguments after it are read.
+pub fn decode_jupiter_instruction(data: &[u8]) -> Result<JupiterSwapParams> {
+    require!(!data.is_empty(), ErrorCode::EmptyInstructionData);
+    require!(data.len() >= 8, ErrorCode::UnsupportedJupiterInstruction);
+
+    let discriminator: [u8; 8] = data[..8].try_into().unwrap();
+    let (kind, plan_offset) = match discriminator {
+        ROUTE_DISCRIMINATOR => (JupiterInstructionKind::Route, 8),
+        // id: u8 precedes the route plan
+        SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR => (JupiterInstructionKind::SharedAccountsRoute, 9),
+        EXACT_OUT_ROUTE_DISCRIMINATOR => (JupiterInstructionKind::ExactOutRoute, 8),
+        _ => return err!(ErrorCode::UnsupportedJupiterInstruction),
+    };
+
+    // Route plan: u32 length prefix followed by at least one step
+    require!(
+        data.len() >= plan_offset + 4 + ARGS_TAIL_LEN,
+        ErrorCode::UnsupportedJupiterInstruction
+    );
+    let steps = u32::from_le_bytes(data[plan_offset..plan_offset + 4].try_into().unwrap());
+    require!(steps > 0, ErrorCode::InvalidRoute);
+
+    let tail = &data[data.len() - ARGS_TAIL_LEN..];
+    let first = u64::from_le_bytes(tail[0..8].try_into().unwrap());
+    let second = u64::from_le_bytes(tail[8..16].try_into().unwrap());
+    let slippage_bps = u16::from_le_bytes(tail[16..18].try_into().unwrap());
+    let platform_fee_bps = tail[18];
+
+    // exact_out_route carries (out_amount, quoted_in_amount)
+    let (in_amount, quoted_out_amount) = match kind {
+        JupiterInstructionKind::ExactOutRoute => (second, first),
+        _ => (first, second),
+    };
+    require!(in_amount > 0 && quoted_out_amount > 0, ErrorCode::InvalidSwapAmount);
+
+    Ok(JupiterSwapParams {
+        kind,
+        in_amount,
+        quoted_out_amount,
+        slippage_bps,
+        platform_fee_bps,
+    })
+}
+
+impl JupiterSwapParams {
+    /// Check the decoded arguments against router policy
+    pub fn enforce_policy(&self, policy: &JupiterPolicy) -> Result<()> {
+        require!(self.slippage_bps <= 10_000, ErrorCode::SlippageToleranceTooHigh);
+        if policy.max_slippage_bps > 0 {
+            require!(
+                self.slippage_bps <= policy.max_slippage_bps,
+                ErrorCode::SlippageToleranceTooHigh
+            );
+        }
+        if let Some(max_amount_in) = policy.max_amount_in {
+            require!(self.in_amount <= max_amount_in, ErrorCode::SlippageExceeded);
+        }
+        if !policy.allow_platform_fee {
+            require!(self.platform_fee_bps == 0, ErrorCode::PlatformFeeNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Check that the instruction trades between the expected token accounts
+    pub fn validate_accounts(
+        &self,
+        accounts: &[AccountInfo],
+        expected: &ExpectedSwapAccounts,
+    ) -> Result<()> {
+        let layout = self.kind.account_layout();
+        require!(
+            accounts.len() > layout.destination_mint.max(layout.destination_token),
+            ErrorCode::JupiterAccountMismatch
+        );
+
+        require!(
+            accounts[layout.source_token].key() == expected.source_token
+                && accounts[layout.destination_token].key() == expected.destination_token,
+            ErrorCode::JupiterAccountMismatch
+        );
+        if let Some(index) = layout.destination_override {
+            let key = accounts[index].key();
+            require!(
+                key == crate::router::JUPITER_V6 || key == expected.destination_token,
+                ErrorCode::JupiterAccountMismatch
+            );
+        }
+
+        if let Some(index) = layout.source_mint {
+            require!(
+                accounts[index].key() == expected.source_mint,
+                ErrorCode::TokenMintMismatch
+            );
+        }
+        require!(
+            accounts[layout.destination_mint].key() == expected.destination_mint,
+            ErrorCode::TokenMintMismatch
+        );
+        Ok(())
+    }
+
+    /// Check a measured output against the quote, within the router's own
+    /// slippage limit (no-op when the router has no limit)
+    pub fn validate_output(&self, amount_out: u64, max_slippage_bps: u16) -> Result<()> {
+        if max_slippage_bps == 0 || self.kind == JupiterInstructionKind::ExactOutRoute {
+            return Ok(());
+        }
+        validate_slippage(self.quoted_out_amount, amount_out, max_slippage_bps)
+    }
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::tokens::*;
+use crate::utils::*;
+
+// Upper bound for the bounty paid to keepers filling orders
+pub const MAX_KEEPER_BOUNTY_BPS: u16 = 1_000;
+
+/// How the output of a keeper-executed swap is divided
+#[derive(Clone, Copy, Debug)]
+pub struct KeeperSplit {
+    pub fee_amount: u64,
+    pub keeper_bounty: u64,
+    pub user_amount: u64,
+}
+
+impl KeeperSplit {
+    /// Router fee and keeper bounty are both taken from the gross output
+    pub fn new(amount_out: u64, fee_rate_bps: u16, keeper_bounty_bps: u16) -> Result<Self> {
+        let fee_amount = calculate_fee(amount_out, fee_rate_bps)?;
+        let keeper_bounty = calculate_fee(amount_out, keeper_bounty_bps)?;
+        let user_amount = amount_out
+            .checked_sub(fee_amount)
+            .and_then(|v| v.checked_sub(keeper_bounty))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(Self { fee_amount, keeper_bounty, user_amount })
+    }
+}
+
+/// Router-owned output account of a keeper swap and where its contents go
+pub struct KeeperPayout<'a, 'info> {
+    pub source: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub authority: &'a AccountInfo<'info>,
+    pub fee_treasury: &'a AccountInfo<'info>,
+    pub keeper_token: &'a AccountInfo<'info>,
+    pub user_destination: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> KeeperPayout<'a, 'info> {
+    /// Pay the treasury, keeper and user from `source`, signed by `authority`.
+    /// Returns the fee that reached the treasury, net of any transfer fee.
+    pub fn pay(&self, split: &KeeperSplit, signer_seeds: &[&[&[u8]]]) -> Result<u64> {
+        for (to, amount) in [
+            (self.fee_treasury, split.fee_amount),
+            (self.keeper_token, split.keeper_bounty),
+            (self.user_destination, split.user_amount),
+        ] {
+            if amount > 0 {
+                transfer_tokens(
+                    self.source,
+                    self.mint,
+                    to,
+                    self.authority,
+                    &[self.token_program],
+                    amount,
+                    signer_seeds,
+                )?;
+            }
+        }
+        amount_after_transfer_fee(self.mint, split.fee_amount)
+    }
+}
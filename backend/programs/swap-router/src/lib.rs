@@ -1,7 +1,35 @@
 pub mod router;
 pub mod utils;
 pub mod errors;
+pub mod events;
+pub mod swap;
+pub mod registry;
+pub mod pool;
+pub mod route;
+pub mod jupiter;
+pub mod exact_out;
+pub mod partner;
+pub mod user_stats;
+pub mod tokens;
+pub mod oracle;
+pub mod keeper;
+pub mod limit_order;
+pub mod dca;
 
 pub use router::*;
 pub use utils::*;
-pub use errors::*;
\ No newline at end of file
+pub use errors::*;
+pub use events::*;
+pub use swap::*;
+pub use registry::*;
+pub use pool::*;
+pub use route::*;
+pub use jupiter::*;
+pub use exact_out::*;
+pub use partner::*;
+pub use user_stats::*;
+pub use tokens::*;
+pub use oracle::*;
+pub use keeper::*;
+pub use limit_order::*;
+pub use dca::*;
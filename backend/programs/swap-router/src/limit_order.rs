@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+use crate::errors::ErrorCode;
+use crate::router::{RouterState, JUPITER_V6};
+use crate::tokens::harvest_withheld_fees;
+
+/// Escrowed order filled by keepers once Jupiter can meet the floor
+/// (seeds: ["limit_order", owner, order_id])
+/// The order PDA owns both escrows and signs the Jupiter CPI, so a keeper's
+/// payload can only ever spend this order's input.
+#[account]
+pub struct LimitOrder {
+    pub owner: Pubkey,              // 32
+    pub order_id: u64,              // 8  (chosen by the owner)
+    pub input_mint: Pubkey,         // 32
+    pub output_mint: Pubkey,        // 32
+    pub destination: Pubkey,        // 32 (owner token account credited on fill)
+    pub input_amount: u64,          // 8  (escrowed, net of any transfer fee)
+    pub min_output_amount: u64,     // 8  (floor on what reaches `destination`)
+    pub expiry_unix_ts: i64,        // 8
+    pub keeper_bounty_bps: u16,     // 2  (router bounty when placed)
+    pub bump: u8,                   // 1
+}
+
+impl LimitOrder {
+    pub const INIT_SPACE: usize = 32 + 8 + 32 + 32 + 32 + 8 + 8 + 8 + 2 + 1;
+}
+
+/// Close one of the order's escrows, rent to `destination`.
+/// Transfer fees withheld in the escrow are harvested to `mint` first.
+pub fn close_order_escrow<'info>(
+    escrow: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    order: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    harvest_withheld_fees(escrow, mint, token_program)?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: escrow.clone(),
+            destination: destination.clone(),
+            authority: order.clone(),
+        },
+        signer_seeds,
+    ))
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(seeds = [b"router_state"], bump = router_state.bump)]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LimitOrder::INIT_SPACE,
+        seeds = [b"limit_order", owner.key().as_ref(), order_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"limit_order_escrow", order.key().as_ref()],
+        bump,
+        token::mint = input_mint,
+        token::authority = order,
+        token::token_program = input_token_program
+    )]
+    pub input_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the Jupiter output before it is split
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"limit_order_output", order.key().as_ref()],
+        bump,
+        token::mint = output_mint,
+        token::authority = order,
+        token::token_program = output_token_program
+    )]
+    pub output_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    pub input_mint: InterfaceAccount<'info, Mint>,
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = input_mint, token::authority = owner)]
+    pub owner_source_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(token::mint = output_mint, token::authority = owner)]
+    pub owner_destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(address = *input_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub input_token_program: Interface<'info, TokenInterface>,
+    #[account(address = *output_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub output_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"limit_order", owner.key().as_ref(), order.order_id.to_le_bytes().as_ref()],
+        bump = order.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    #[account(mut, seeds = [b"limit_order_escrow", order.key().as_ref()], bump)]
+    pub input_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"limit_order_output", order.key().as_ref()], bump)]
+    pub output_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Mints are writable to receive fees withheld in the escrows
+    #[account(mut, address = order.input_mint @ ErrorCode::TokenMintMismatch)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, address = order.output_mint @ ErrorCode::TokenMintMismatch)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    /// Receives the escrowed input back
+    #[account(mut, token::mint = input_mint, token::authority = owner)]
+    pub owner_source_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives anything sent to the output escrow so it can be closed
+    #[account(mut, address = order.destination @ ErrorCode::InvalidOrderDestination)]
+    pub owner_destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(address = *input_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub input_token_program: Interface<'info, TokenInterface>,
+    #[account(address = *output_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub output_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FillLimitOrder<'info> {
+    #[account(mut, seeds = [b"router_state"], bump = router_state.bump)]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        mut,
+        seeds = [b"limit_order", owner.key().as_ref(), order.order_id.to_le_bytes().as_ref()],
+        bump = order.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner
+    )]
+    pub order: Account<'info, LimitOrder>,
+
+    #[account(mut, seeds = [b"limit_order_escrow", order.key().as_ref()], bump)]
+    pub input_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, seeds = [b"limit_order_output", order.key().as_ref()], bump)]
+    pub output_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Mints are writable to receive fees withheld in the escrows
+    #[account(mut, address = order.input_mint @ ErrorCode::TokenMintMismatch)]
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, address = order.output_mint @ ErrorCode::TokenMintMismatch)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Order owner (has_one); receives the escrow rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut, address = order.destination @ ErrorCode::InvalidOrderDestination)]
+    pub owner_destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", output_mint.key().as_ref()],
+        bump
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub keeper: Signer<'info>,
+
+    /// Keeper account receiving the bounty in the output mint
+    #[account(mut, token::mint = output_mint)]
+    pub keeper_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Jupiter V6 Program
+    #[account(constraint = jupiter_program.key() == JUPITER_V6 @ ErrorCode::InvalidJupiterProgram)]
+    pub jupiter_program: AccountInfo<'info>,
+
+    #[account(address = *input_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub input_token_program: Interface<'info, TokenInterface>,
+    #[account(address = *output_mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Price feed PDAs and oracles for both mints, as on SwapAccounts;
+    /// the feeds are required while the oracle guard is on
+    #[account(seeds = [b"price_feed", order.input_mint.as_ref()], bump)]
+    pub input_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Oracle named by input_price_feed, validated when read
+    pub input_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: See input_price_feed
+    #[account(seeds = [b"price_feed", order.output_mint.as_ref()], bump)]
+    pub output_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Oracle named by output_price_feed, validated when read
+    pub output_oracle: Option<UncheckedAccount<'info>>,
+
+    // Jupiter route accounts are passed via remaining_accounts, with
+    // `input_escrow` as the source, `output_escrow` as the destination and
+    // `order` as the transfer authority
+}
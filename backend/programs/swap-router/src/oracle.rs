@@ -0,0 +1,310 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::errors::ErrorCode;
+use crate::router::RouterState;
+
+// Pyth Solana receiver program, owner of PriceUpdateV2 accounts
+pub const PYTH_RECEIVER: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+// Anchor discriminator of the Pyth `PriceUpdateV2` account
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+// PriceUpdateV2: discriminator, write_authority, verification_level (Full = 1)
+const PRICE_UPDATE_VERIFICATION_OFFSET: usize = 8 + 32;
+const VERIFICATION_LEVEL_FULL: u8 = 1;
+// price_message after a Full verification level:
+// feed_id [u8; 32], price i64, conf u64, exponent i32, publish_time i64, ...
+const PRICE_MESSAGE_OFFSET: usize = PRICE_UPDATE_VERIFICATION_OFFSET + 1;
+const PRICE_MESSAGE_LEN: usize = 32 + 8 + 8 + 4 + 8;
+
+/// Where a mint's price comes from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OracleKind {
+    /// Pyth pull oracle `PriceUpdateV2` account
+    Pyth,
+    /// Router-owned PushOracle updated by a designated pusher
+    Push,
+}
+
+/// Oracle registered for a mint (seeds: ["price_feed", mint])
+#[account]
+pub struct PriceFeed {
+    pub mint: Pubkey,           // 32
+    pub decimals: u8,           // 1
+    pub kind: OracleKind,       // 1
+    pub oracle: Pubkey,         // 32 (PriceUpdateV2 or PushOracle account)
+    pub feed_id: [u8; 32],      // 32 (Pyth feed id, zero for Push)
+    pub bump: u8,               // 1
+}
+
+impl PriceFeed {
+    pub const INIT_SPACE: usize = 32 + 1 + 1 + 32 + 32 + 1;
+
+    /// Read the price from `oracle`, rejecting it if older than `max_staleness_secs`
+    pub fn read_price(&self, oracle: &AccountInfo, now: i64, max_staleness_secs: u32) -> Result<OraclePrice> {
+        require_keys_eq!(*oracle.key, self.oracle, ErrorCode::InvalidOracleAccount);
+
+        let price = match self.kind {
+            OracleKind::Pyth => read_pyth_price(oracle, &self.feed_id)?,
+            OracleKind::Push => {
+                require_keys_eq!(*oracle.owner, crate::ID, ErrorCode::InvalidOracleAccount);
+                let push_oracle = PushOracle::try_deserialize(&mut &oracle.try_borrow_data()?[..])?;
+                require_keys_eq!(push_oracle.mint, self.mint, ErrorCode::InvalidOracleAccount);
+                OraclePrice {
+                    price: push_oracle.price,
+                    expo: push_oracle.expo,
+                    publish_time: push_oracle.publish_time,
+                }
+            }
+        };
+
+        require!(price.price > 0, ErrorCode::InvalidOracleAccount);
+        require!(
+            now.saturating_sub(price.publish_time) <= max_staleness_secs as i64,
+            ErrorCode::StaleOraclePrice
+        );
+        Ok(price)
+    }
+}
+
+/// Price written by the router's own pusher (seeds: ["push_oracle", mint])
+#[account]
+pub struct PushOracle {
+    pub mint: Pubkey,           // 32
+    pub pusher: Pubkey,         // 32 (only signer allowed to push)
+    pub price: i64,             // 8  (price * 10^-expo, in the quote currency)
+    pub expo: i32,              // 4
+    pub publish_time: i64,      // 8  (unix timestamp of the last push)
+    pub bump: u8,               // 1
+}
+
+impl PushOracle {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 4 + 8 + 1;
+}
+
+/// Price of one whole token: price * 10^expo
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+fn read_pyth_price(oracle: &AccountInfo, feed_id: &[u8; 32]) -> Result<OraclePrice> {
+    require_keys_eq!(*oracle.owner, PYTH_RECEIVER, ErrorCode::InvalidOracleAccount);
+
+    let data = oracle.try_borrow_data()?;
+    require!(
+        data.len() >= PRICE_MESSAGE_OFFSET + PRICE_MESSAGE_LEN
+            && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+        ErrorCode::InvalidOracleAccount
+    );
+    // Partially verified updates are not trusted
+    require!(
+        data[PRICE_UPDATE_VERIFICATION_OFFSET] == VERIFICATION_LEVEL_FULL,
+        ErrorCode::InvalidOracleAccount
+    );
+
+    let message = &data[PRICE_MESSAGE_OFFSET..PRICE_MESSAGE_OFFSET + PRICE_MESSAGE_LEN];
+    require!(message[..32] == feed_id[..], ErrorCode::InvalidOracleAccount);
+    Ok(OraclePrice {
+        price: i64::from_le_bytes(message[32..40].try_into().unwrap()),
+        expo: i32::from_le_bytes(message[48..52].try_into().unwrap()),
+        publish_time: i64::from_le_bytes(message[52..60].try_into().unwrap()),
+    })
+}
+
+/// Output the oracles imply for `amount_in`, in raw output units
+pub fn oracle_amount_out(
+    amount_in: u64,
+    input_decimals: u8,
+    input_price: &OraclePrice,
+    output_decimals: u8,
+    output_price: &OraclePrice,
+) -> Result<u128> {
+    // amount_in * p_in * 10^(e_in - d_in) / (p_out * 10^(e_out - d_out))
+    let exponent = input_price.expo as i64 - output_price.expo as i64
+        + output_decimals as i64 - input_decimals as i64;
+    let scale = 10u128
+        .checked_pow(exponent.unsigned_abs() as u32)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let mut numerator = (amount_in as u128)
+        .checked_mul(input_price.price as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let mut denominator = output_price.price as u128;
+    if exponent >= 0 {
+        numerator = numerator.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?;
+    } else {
+        denominator = denominator.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?;
+    }
+    Ok(numerator / denominator)
+}
+
+/// Reject executions whose output is more than `max_deviation_bps` away
+/// from what the oracles imply
+pub fn check_price_deviation(amount_out: u64, oracle_out: u128, max_deviation_bps: u16) -> Result<()> {
+    require!(oracle_out > 0, ErrorCode::OraclePriceDeviation);
+    let deviation_bps = (amount_out as u128).abs_diff(oracle_out)
+        .checked_mul(10_000)
+        .ok_or(ErrorCode::MathOverflow)?
+        / oracle_out;
+    require!(deviation_bps <= max_deviation_bps as u128, ErrorCode::OraclePriceDeviation);
+    Ok(())
+}
+
+/// Price feed PDAs and oracles for both sides of a swap
+pub struct OracleGuardAccounts<'a, 'info> {
+    pub input_price_feed: Option<&'a AccountInfo<'info>>,
+    pub input_oracle: Option<&'a AccountInfo<'info>>,
+    pub output_price_feed: Option<&'a AccountInfo<'info>>,
+    pub output_oracle: Option<&'a AccountInfo<'info>>,
+}
+
+impl OracleGuardAccounts<'_, '_> {
+    /// Enforce the oracle guard on measured amounts. While the guard is on
+    /// both price feed PDAs must be passed (their seeds are checked by the
+    /// account constraints). An empty PDA means the mint has no feed and the
+    /// swap cannot be priced; once both mints have feeds both oracles are required.
+    pub fn check(&self, router: &RouterState, amount_in: u64, amount_out: u64, now: i64) -> Result<()> {
+        if router.max_price_deviation_bps == 0 {
+            return Ok(());
+        }
+        let (Some(input_feed), Some(output_feed)) = (
+            load_price_feed(self.input_price_feed)?,
+            load_price_feed(self.output_price_feed)?,
+        ) else {
+            return Ok(());
+        };
+        let (Some(input_oracle), Some(output_oracle)) = (self.input_oracle, self.output_oracle) else {
+            return err!(ErrorCode::InvalidOracleAccount);
+        };
+
+        let staleness = router.max_oracle_staleness_secs;
+        let input_price = input_feed.read_price(input_oracle, now, staleness)?;
+        let output_price = output_feed.read_price(output_oracle, now, staleness)?;
+        let oracle_out = oracle_amount_out(
+            amount_in,
+            input_feed.decimals,
+            &input_price,
+            output_feed.decimals,
+            &output_price,
+        )?;
+        check_price_deviation(amount_out, oracle_out, router.max_price_deviation_bps)
+    }
+}
+
+/// The feed registered at a price feed PDA, or None for an empty PDA
+fn load_price_feed(info: Option<&AccountInfo>) -> Result<Option<PriceFeed>> {
+    let info = info.ok_or(ErrorCode::MissingPriceFeed)?;
+    if *info.owner != crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(PriceFeed::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+}
+
+/// Check that a feed registration points at a usable source for `mint`
+pub fn validate_price_feed_source(
+    mint: &Pubkey,
+    kind: OracleKind,
+    oracle: &Pubkey,
+    feed_id: &[u8; 32],
+) -> Result<()> {
+    match kind {
+        OracleKind::Pyth => require!(*feed_id != [0; 32], ErrorCode::InvalidOracleConfig),
+        OracleKind::Push => {
+            let (push_oracle, _) = Pubkey::find_program_address(
+                &[b"push_oracle", mint.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(*oracle, push_oracle, ErrorCode::InvalidOracleConfig);
+        }
+    }
+    Ok(())
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[derive(Accounts)]
+pub struct RegisterPriceFeed<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [b"price_feed", mint.key().as_ref()],
+        bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceFeed<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        mut,
+        seeds = [b"price_feed", price_feed.mint.as_ref()],
+        bump = price_feed.bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePushOracle<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PushOracle::INIT_SPACE,
+        seeds = [b"push_oracle", mint.key().as_ref()],
+        bump
+    )]
+    pub push_oracle: Account<'info, PushOracle>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PushPrice<'info> {
+    #[account(
+        mut,
+        seeds = [b"push_oracle", push_oracle.mint.as_ref()],
+        bump = push_oracle.bump,
+        has_one = pusher @ ErrorCode::Unauthorized
+    )]
+    pub push_oracle: Account<'info, PushOracle>,
+
+    pub pusher: Signer<'info>,
+}
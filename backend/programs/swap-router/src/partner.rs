@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::errors::ErrorCode;
+use crate::router::RouterState;
+
+// Upper bound for a partner's share of the router fee
+pub const MAX_PARTNER_SHARE_BPS: u16 = 10_000;
+
+/// Integrator earning a share of the router fee (seeds: ["partner", authority])
+#[account]
+pub struct Partner {
+    pub authority: Pubkey,          // 32 (claims the accrued fees)
+    pub revenue_share_bps: u16,     // 2  (share of the router fee, 10000 = all of it)
+    pub active: bool,               // 1
+    pub swap_count: u64,            // 8
+    pub total_fees_accrued: u64,    // 8
+    pub total_fees_claimed: u64,    // 8
+    pub bump: u8,                   // 1
+}
+
+impl Partner {
+    pub const INIT_SPACE: usize = 32 + 2 + 1 + 8 + 8 + 8 + 1;
+
+    /// Partner's cut of a router fee
+    pub fn share_of(&self, fee_amount: u64) -> Result<u64> {
+        let share = (fee_amount as u128)
+            .checked_mul(self.revenue_share_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / 10_000;
+        Ok(share as u64)
+    }
+
+    pub fn record_accrual(&mut self, amount: u64) -> Result<()> {
+        self.swap_count = self.swap_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.total_fees_accrued = self.total_fees_accrued
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[derive(Accounts)]
+#[instruction(partner_authority: Pubkey)]
+pub struct RegisterPartner<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Partner::INIT_SPACE,
+        seeds = [b"partner", partner_authority.as_ref()],
+        bump
+    )]
+    pub partner: Account<'info, Partner>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePartner<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        mut,
+        seeds = [b"partner", partner.authority.as_ref()],
+        bump = partner.bump
+    )]
+    pub partner: Account<'info, Partner>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Token account collecting a partner's share for one mint.
+/// Anyone may pay for it; swaps crediting the partner in that mint require it.
+#[derive(Accounts)]
+pub struct InitializePartnerFeeAccount<'info> {
+    #[account(seeds = [b"partner", partner.authority.as_ref()], bump = partner.bump)]
+    pub partner: Account<'info, Partner>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"partner_fees", partner.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = partner,
+        token::token_program = token_program
+    )]
+    pub partner_fee_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(address = *mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPartnerFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"partner", authority.key().as_ref()],
+        bump = partner.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub partner: Account<'info, Partner>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut, token::authority = partner)]
+    pub partner_fee_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = partner_fee_account.mint @ ErrorCode::TokenMintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = partner_fee_account.mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = *mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
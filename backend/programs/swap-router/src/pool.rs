@@ -0,0 +1,245 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::errors::ErrorCode;
+use crate::swap::*;
+use crate::tokens::*;
+use crate::utils::*;
+
+// LP tokens permanently locked on the first deposit (never minted)
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+// Decimals of every pool LP mint
+pub const LP_DECIMALS: u8 = 9;
+
+// LP fee on every pool swap (0.25%), left in the reserves for liquidity providers
+pub const POOL_FEE_BPS: u16 = 25;
+
+/// Native x*y=k pool (seeds: ["pool", mint_a, mint_b], mint_a < mint_b)
+/// Reserves are the balances of vault_a / vault_b
+#[account]
+pub struct Pool {
+    pub mint_a: Pubkey,         // 32
+    pub mint_b: Pubkey,         // 32
+    pub vault_a: Pubkey,        // 32
+    pub vault_b: Pubkey,        // 32
+    pub lp_mint: Pubkey,        // 32
+    pub lp_supply: u64,         // 8 (minted LP + MINIMUM_LIQUIDITY)
+    pub bump: u8,               // 1
+}
+
+impl Pool {
+    pub const INIT_SPACE: usize = 32 * 5 + 8 + 1;
+}
+
+/// A pool together with its vaults and mints, used to swap against the curve
+pub struct PoolLeg<'a, 'info> {
+    pub pool: &'a Account<'info, Pool>,
+    pub vault_a: &'a AccountInfo<'info>,
+    pub vault_b: &'a AccountInfo<'info>,
+    pub mint_a: &'a AccountInfo<'info>,
+    pub mint_b: &'a AccountInfo<'info>,
+}
+
+/// Result of a swap against a native pool
+#[derive(Clone, Copy, Debug)]
+pub struct PoolSwapResult {
+    pub amount_out: u64,
+    pub price_impact_bps: u16,
+}
+
+impl<'a, 'info> PoolLeg<'a, 'info> {
+    /// Move `amount_in` from the user into the pool and the curve output back.
+    /// Direction follows the mints of the user's token accounts.
+    pub fn swap(
+        &self,
+        user_source: &AccountInfo<'info>,
+        user_destination: &AccountInfo<'info>,
+        user: &AccountInfo<'info>,
+        token_programs: &[&AccountInfo<'info>],
+        amount_in: u64,
+    ) -> Result<PoolSwapResult> {
+        let pool = self.pool;
+        require!(
+            self.vault_a.key() == pool.vault_a
+                && self.vault_b.key() == pool.vault_b
+                && self.mint_a.key() == pool.mint_a
+                && self.mint_b.key() == pool.mint_b,
+            ErrorCode::PoolMismatch
+        );
+
+        let source_mint = load_token_account(user_source)?.mint;
+        let destination_mint = load_token_account(user_destination)?.mint;
+
+        let (vault_in, vault_out, mint_in, mint_out) =
+            if source_mint == pool.mint_a && destination_mint == pool.mint_b {
+                (self.vault_a, self.vault_b, self.mint_a, self.mint_b)
+            } else if source_mint == pool.mint_b && destination_mint == pool.mint_a {
+                (self.vault_b, self.vault_a, self.mint_b, self.mint_a)
+            } else {
+                return err!(ErrorCode::InvalidTokenPair);
+            };
+
+        // The curve only sees what reaches the vault after any transfer fee
+        let reserve_in = token_balance(vault_in)?;
+        let reserve_out = token_balance(vault_out)?;
+        let received_in = amount_after_transfer_fee(mint_in, amount_in)?;
+        let amount_out = calculate_swap_output(received_in, reserve_in, reserve_out, POOL_FEE_BPS)?;
+        let price_impact_bps = calculate_price_impact(received_in, reserve_in)?;
+
+        transfer_tokens(user_source, mint_in, vault_in, user, token_programs, amount_in, &[])?;
+
+        let seeds = &[b"pool".as_ref(), pool.mint_a.as_ref(), pool.mint_b.as_ref(), &[pool.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        transfer_tokens(
+            vault_out,
+            mint_out,
+            user_destination,
+            &pool.to_account_info(),
+            token_programs,
+            amount_out,
+            signer_seeds,
+        )?;
+
+        Ok(PoolSwapResult {
+            amount_out,
+            price_impact_bps,
+        })
+    }
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(constraint = mint_a.key() < mint_b.key() @ ErrorCode::InvalidTokenPair)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_vault", pool.key().as_ref(), mint_a.key().as_ref()],
+        bump,
+        token::mint = mint_a,
+        token::authority = pool,
+        token::token_program = token_program_a
+    )]
+    pub vault_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_vault", pool.key().as_ref(), mint_b.key().as_ref()],
+        bump,
+        token::mint = mint_b,
+        token::authority = pool,
+        token::token_program = token_program_b
+    )]
+    pub vault_b: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"pool_lp", pool.key().as_ref()],
+        bump,
+        mint::decimals = LP_DECIMALS,
+        mint::authority = pool
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Program of the LP mint (legacy SPL Token)
+    pub token_program: Program<'info, Token>,
+
+    /// Programs owning mint_a / mint_b (SPL Token or Token-2022)
+    #[account(address = *mint_a.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub token_program_a: Interface<'info, TokenInterface>,
+    #[account(address = *mint_b.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub token_program_b: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Shared by add_liquidity and remove_liquidity
+#[derive(Accounts)]
+pub struct ModifyLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a @ ErrorCode::PoolMismatch,
+        has_one = vault_b @ ErrorCode::PoolMismatch,
+        has_one = lp_mint @ ErrorCode::PoolMismatch,
+        has_one = mint_a @ ErrorCode::PoolMismatch,
+        has_one = mint_b @ ErrorCode::PoolMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub vault_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub vault_b: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    pub user: Signer<'info>,
+
+    #[account(mut, token::mint = pool.mint_a, token::authority = user)]
+    pub user_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = pool.mint_b, token::authority = user)]
+    pub user_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = lp_mint, token::authority = user)]
+    pub user_lp_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Program of the LP mint; pool mints may belong to either program
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct PoolSwap<'info> {
+    pub swap: SwapAccounts<'info>,
+
+    #[account(
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a @ ErrorCode::PoolMismatch,
+        has_one = vault_b @ ErrorCode::PoolMismatch,
+        has_one = mint_a @ ErrorCode::PoolMismatch,
+        has_one = mint_b @ ErrorCode::PoolMismatch
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub vault_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub vault_b: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    pub mint_b: InterfaceAccount<'info, Mint>,
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::router::RouterState;
+
+// Maximum number of DEX programs the registry can hold
+pub const MAX_DEX_PROGRAMS: usize = 16;
+
+/// How the router talks to a registered DEX program
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdapterKind {
+    /// Jupiter v6: instructions are decoded and checked against the swap accounts
+    Jupiter,
+    RaydiumAmm,
+    OrcaWhirlpool,
+    Meteora,
+    /// The router's own pools; route hops only, never a registry entry
+    NativePool,
+}
+
+impl AdapterKind {
+    /// Kinds without a decoder of their own: instruction data is forwarded
+    /// as-is and only the user's balance deltas are checked
+    pub fn is_passthrough(&self) -> bool {
+        matches!(
+            self,
+            AdapterKind::RaydiumAmm | AdapterKind::OrcaWhirlpool | AdapterKind::Meteora
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct DexEntry {
+    pub program_id: Pubkey,     // 32
+    pub kind: AdapterKind,      // 1
+}
+
+impl DexEntry {
+    pub const INIT_SPACE: usize = 32 + 1;
+}
+
+/// DEX programs allowed as swap targets (seeds: ["dex_registry"])
+/// Managed by the router authority
+#[account]
+pub struct DexRegistry {
+    pub entries: Vec<DexEntry>, // 4 + 33 * MAX_DEX_PROGRAMS
+    pub bump: u8,               // 1
+}
+
+impl DexRegistry {
+    pub const INIT_SPACE: usize = 4 + DexEntry::INIT_SPACE * MAX_DEX_PROGRAMS + 1;
+
+    /// Adapter kind for a registered program, or InvalidDexProgram
+    pub fn adapter_kind(&self, program_id: &Pubkey) -> Result<AdapterKind> {
+        self.entries
+            .iter()
+            .find(|e| e.program_id == *program_id)
+            .map(|e| e.kind)
+            .ok_or(ErrorCode::InvalidDexProgram.into())
+    }
+
+    /// Add a program or update its adapter kind
+    pub fn upsert(&mut self, program_id: Pubkey, kind: AdapterKind) -> Result<()> {
+        require!(kind != AdapterKind::NativePool, ErrorCode::InvalidDexProgram);
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.program_id == program_id) {
+            entry.kind = kind;
+            return Ok(());
+        }
+        require!(self.entries.len() < MAX_DEX_PROGRAMS, ErrorCode::DexRegistryFull);
+        self.entries.push(DexEntry { program_id, kind });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, program_id: &Pubkey) -> Result<()> {
+        let len_before = self.entries.len();
+        self.entries.retain(|e| e.program_id != *program_id);
+        require!(self.entries.len() < len_before, ErrorCode::InvalidDexProgram);
+        Ok(())
+    }
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[derive(Accounts)]
+pub struct InitializeDexRegistry<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DexRegistry::INIT_SPACE,
+        seeds = [b"dex_registry"],
+        bump
+    )]
+    pub dex_registry: Account<'info, DexRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDexRegistry<'info> {
+    #[account(
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+
+    #[account(
+        mut,
+        seeds = [b"dex_registry"],
+        bump = dex_registry.bump
+    )]
+    pub dex_registry: Account<'info, DexRegistry>,
+
+    pub authority: Signer<'info>,
+}
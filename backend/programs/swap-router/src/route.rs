@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use crate::errors::ErrorCode;
+use crate::jupiter::*;
+use crate::pool::*;
+use crate::registry::*;
+use crate::swap::*;
+
+// Maximum number of hops in a single route
+pub const MAX_ROUTE_HOPS: usize = 4;
+
+/// How much of its input token a hop may spend
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HopAmount {
+    Exact(u64),
+    /// Everything the previous hop produced (not valid for the first hop)
+    AllOfPrevious,
+}
+
+/// One leg of a route.
+/// `accounts_start..accounts_start + accounts_len` indexes remaining_accounts:
+/// - NativePool: [pool, vault_a, vault_b, mint_a, mint_b], executed by the router itself
+/// - other adapters: [dex_program, ...dex accounts], `data` forwarded as-is
+///   (Jupiter payloads are decoded and checked against the hop first)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RouteHop {
+    pub adapter: AdapterKind,
+    pub accounts_start: u8,
+    pub accounts_len: u8,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount: HopAmount,
+    pub data: Vec<u8>,
+}
+
+/// Amounts measured over a whole route
+#[derive(Clone, Copy, Debug)]
+pub struct RouteResult {
+    /// Spent by the first hop
+    pub amount_in: u64,
+    /// Received from the last hop
+    pub amount_out: u64,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRoute<'info> {
+    /// `user_source_token` feeds the first hop, `user_destination_token`
+    /// receives the last hop; they may be the same account for round trips
+    pub swap: SwapAccounts<'info>,
+
+    #[account(seeds = [b"dex_registry"], bump = dex_registry.bump)]
+    pub dex_registry: Account<'info, DexRegistry>,
+
+    // remaining_accounts:
+    // [0 .. hops - 1]  user token accounts for the intermediate mints, in hop order
+    // [hops - 1 ..]    hop account ranges referenced by RouteHop
+}
+
+/// Check that hops chain from `source_mint` to `destination_mint` and that
+/// every account range fits in remaining_accounts
+pub fn validate_route(
+    hops: &[RouteHop],
+    source_mint: &Pubkey,
+    destination_mint: &Pubkey,
+    remaining_len: usize,
+) -> Result<()> {
+    require!(
+        !hops.is_empty() && hops.len() <= MAX_ROUTE_HOPS,
+        ErrorCode::InvalidRoute
+    );
+    require!(
+        matches!(hops[0].amount, HopAmount::Exact(amount) if amount > 0),
+        ErrorCode::InvalidRoute
+    );
+    require!(hops[0].input_mint == *source_mint, ErrorCode::InvalidRoute);
+    require!(
+        hops[hops.len() - 1].output_mint == *destination_mint,
+        ErrorCode::InvalidRoute
+    );
+
+    for (i, hop) in hops.iter().enumerate() {
+        require!(hop.input_mint != hop.output_mint, ErrorCode::InvalidTokenPair);
+        if i > 0 {
+            require!(hops[i - 1].output_mint == hop.input_mint, ErrorCode::InvalidRoute);
+        }
+
+        let start = hop.accounts_start as usize;
+        let end = start + hop.accounts_len as usize;
+        require!(
+            hop.accounts_len > 0 && start >= hops.len() - 1 && end <= remaining_len,
+            ErrorCode::InvalidRoute
+        );
+    }
+
+    Ok(())
+}
+
+impl<'info> ExecuteRoute<'info> {
+    /// Execute every hop in order, checking the user's balances between hops
+    pub fn execute_hops(
+        &self,
+        hops: &[RouteHop],
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<RouteResult> {
+        validate_route(
+            hops,
+            &self.swap.user_source_token.mint,
+            &self.swap.user_destination_token.mint,
+            remaining_accounts.len(),
+        )?;
+
+        // User token accounts for every mint along the route
+        let intermediates = &remaining_accounts[..hops.len() - 1];
+        let mut chain = Vec::with_capacity(hops.len() + 1);
+        chain.push(self.swap.user_source_token.to_account_info());
+        for (info, hop) in intermediates.iter().zip(hops.iter()) {
+            let token_account = load_token_account(info)?;
+            require!(
+                token_account.owner == self.swap.user.key() && token_account.mint == hop.output_mint,
+                ErrorCode::TokenMintMismatch
+            );
+            chain.push(info.clone());
+        }
+        chain.push(self.swap.user_destination_token.to_account_info());
+
+        let user = self.swap.user.to_account_info();
+        let token_program = self.swap.token_program.to_account_info();
+        let token_2022_program = self.swap.token_2022_program.to_account_info();
+        let token_programs = [&token_program, &token_2022_program];
+        let max_slippage_bps = self.swap.router_state.max_slippage_bps;
+
+        let mut amount_in = 0;
+        let mut previous_out = 0;
+
+        for (i, hop) in hops.iter().enumerate() {
+            let input = &chain[i];
+            let output = &chain[i + 1];
+
+            let amount = match hop.amount {
+                HopAmount::Exact(amount) => amount,
+                HopAmount::AllOfPrevious => {
+                    require!(i > 0, ErrorCode::InvalidRoute);
+                    previous_out
+                }
+            };
+
+            let input_before = token_balance(input)?;
+            let output_before = token_balance(output)?;
+
+            let start = hop.accounts_start as usize;
+            let accounts = &remaining_accounts[start..start + hop.accounts_len as usize];
+            let mut jupiter_params = None;
+
+            match hop.adapter {
+                AdapterKind::NativePool => {
+                    require!(accounts.len() >= 5, ErrorCode::InvalidRoute);
+                    let pool = Account::<Pool>::try_from(&accounts[0])?;
+                    let leg = PoolLeg {
+                        pool: &pool,
+                        vault_a: &accounts[1],
+                        vault_b: &accounts[2],
+                        mint_a: &accounts[3],
+                        mint_b: &accounts[4],
+                    };
+                    leg.swap(input, output, &user, &token_programs, amount)?;
+                }
+                kind => {
+                    let dex_program = &accounts[0];
+                    require!(
+                        self.dex_registry.adapter_kind(dex_program.key)? == kind,
+                        ErrorCode::InvalidDexProgram
+                    );
+                    require!(!hop.data.is_empty(), ErrorCode::EmptyInstructionData);
+
+                    // Jupiter payloads get the same checks as execute_jupiter_swap;
+                    // passthrough kinds only get the balance checks below
+                    if kind == AdapterKind::Jupiter {
+                        let params = decode_jupiter_instruction(&hop.data)?;
+                        params.enforce_policy(&JupiterPolicy {
+                            max_slippage_bps,
+                            max_amount_in: Some(amount),
+                            allow_platform_fee: true,
+                        })?;
+                        params.validate_accounts(
+                            &accounts[1..],
+                            &ExpectedSwapAccounts {
+                                source_token: input.key(),
+                                source_mint: hop.input_mint,
+                                destination_token: output.key(),
+                                destination_mint: hop.output_mint,
+                            },
+                        )?;
+                        jupiter_params = Some(params);
+                    } else {
+                        require!(kind.is_passthrough(), ErrorCode::InvalidDexProgram);
+                    }
+
+                    let dex_ix = passthrough_instruction(
+                        dex_program.key(),
+                        &accounts[1..],
+                        hop.data.clone(),
+                    );
+                    invoke(&dex_ix, accounts)?;
+                }
+            }
+
+            // Intermediate balance checks
+            let spent = input_before
+                .checked_sub(token_balance(input)?)
+                .ok_or(ErrorCode::RouteHopAmountMismatch)?;
+            require!(spent > 0 && spent <= amount, ErrorCode::RouteHopAmountMismatch);
+            let received = token_balance(output)?
+                .checked_sub(output_before)
+                .ok_or(ErrorCode::InsufficientOutputAmount)?;
+            require!(received > 0, ErrorCode::InsufficientOutputAmount);
+            if let Some(params) = jupiter_params {
+                params.validate_output(received, max_slippage_bps)?;
+            }
+
+            msg!(" Hop {} ({:?}): spent {}, received {}", i, hop.adapter, spent, received);
+
+            if i == 0 {
+                amount_in = spent;
+            }
+            previous_out = received;
+        }
+
+        Ok(RouteResult {
+            amount_in,
+            amount_out: previous_out,
+        })
+    }
+}
@@ -1,123 +1,1668 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{
-    instruction::{AccountMeta, Instruction},
-    program::invoke_signed,
-};
-use anchor_spl::token::Token;
-use anchor_lang::system_program::System;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_spl::token::{self, Burn, MintTo};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_lang::system_program::{self, System};
+use crate::errors::ErrorCode;
+use crate::dca::*;
+use crate::events::*;
+use crate::exact_out::*;
+use crate::jupiter::*;
+use crate::keeper::*;
+use crate::limit_order::*;
+use crate::oracle::*;
+use crate::partner::*;
+use crate::pool::*;
+use crate::registry::*;
+use crate::route::*;
+use crate::swap::*;
+use crate::tokens::*;
+use crate::user_stats::*;
+use crate::utils::*;
 
 declare_id!("AgcU7r6U5uPEfFccmhYdMLcjckADdfoJ8QcHCgkG74Zg");
 
 // Jupiter V6 Program ID (Devnet & Mainnet)
 pub const JUPITER_V6: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 
+// Maximum number of vault programs allowed to use execute_vault_jupiter_swap
+pub const MAX_VAULT_PROGRAMS: usize = 4;
+
 /// Swap Router Program
 #[program]
 pub mod swap_router {
     use super::*;
 
-    /// Initialize the router state
-    pub fn initialize_router(
-        ctx: Context<InitializeRouter>,
-        fee_rate_bps: u16,
+    /// Initialize the router state
+    pub fn initialize_router(
+        ctx: Context<InitializeRouter>,
+        fee_rate_bps: u16,
+    ) -> Result<()> {
+        require!(fee_rate_bps <= 1000, ErrorCode::InvalidFeeRate);
+        
+        let router = &mut ctx.accounts.router_state;
+        router.authority = ctx.accounts.authority.key();
+        router.fee_rate_bps = fee_rate_bps;
+        router.total_swaps = 0;
+        router.total_volume = 0;
+        router.bump = ctx.bumps.router_state;
+        router.total_fees_collected = 0;
+        router.pending_authority = Pubkey::default();
+        router.paused = false;
+        router.vault_programs = [Pubkey::default(); MAX_VAULT_PROGRAMS];
+        router.max_slippage_bps = 0;
+        router.fee_tiers = [FeeTier::default(); MAX_FEE_TIERS];
+        router.volume_mints = [Pubkey::default(); MAX_VOLUME_MINTS];
+        
+        msg!(" Router initialized with fee: {} bps", fee_rate_bps);
+        Ok(())
+    }
+
+    /// Create the fee treasury token account for a given mint.
+    /// Owned by the router_state PDA; anyone may pay for it.
+    pub fn initialize_fee_treasury(ctx: Context<InitializeFeeTreasury>) -> Result<()> {
+        validate_mint(&ctx.accounts.mint.to_account_info())?;
+        msg!(" Fee treasury created for mint: {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    /// Create the volume stats account for a given mint.
+    /// Anyone may pay for it; swaps touching the mint require it.
+    pub fn initialize_mint_stats(ctx: Context<InitializeMintStats>) -> Result<()> {
+        validate_mint(&ctx.accounts.mint.to_account_info())?;
+        
+        let stats = &mut ctx.accounts.mint_stats;
+        stats.mint = ctx.accounts.mint.key();
+        stats.swap_count = 0;
+        stats.volume_in = 0;
+        stats.volume_out = 0;
+        stats.fees_collected = 0;
+        stats.bump = ctx.bumps.mint_stats;
+        
+        msg!(" Mint stats created for mint: {}", stats.mint);
+        Ok(())
+    }
+
+    /// Execute a swap via Jupiter aggregator
+    /// All accounts and instruction data come from Jupiter API
+    /// The router fee is skimmed from the output token into the fee treasury
+    /// Optional bounds are checked against the measured balance deltas:
+    /// `min_amount_out` applies to the output net of the router fee
+    /// The swap fails with SwapExpired if it lands after `valid_until_slot`
+    /// or `deadline_unix_ts`
+    pub fn execute_jupiter_swap(
+        ctx: Context<ExecuteJupiterSwap>,
+        jupiter_instruction_data: Vec<u8>,
+        min_amount_out: Option<u64>,
+        max_amount_in: Option<u64>,
+        valid_until_slot: Option<u64>,
+        deadline_unix_ts: Option<i64>,
+    ) -> Result<()> {
+        msg!(" Executing Jupiter swap");
+        
+        require!(!ctx.accounts.swap.router_state.paused, ErrorCode::RouterPaused);
+        require!(
+            !is_expired(&Clock::get()?, valid_until_slot, deadline_unix_ts),
+            ErrorCode::SwapExpired
+        );
+        
+        // Validate Jupiter program
+        require!(
+            ctx.accounts.jupiter_program.key() == JUPITER_V6,
+            ErrorCode::InvalidJupiterProgram
+        );
+        
+        require!(
+            !jupiter_instruction_data.is_empty(),
+            ErrorCode::EmptyInstructionData
+        );
+        
+        // Decode the payload and enforce router policy before forwarding it
+        let max_slippage_bps = ctx.accounts.swap.router_state.max_slippage_bps;
+        let params = decode_jupiter_instruction(&jupiter_instruction_data)?;
+        params.enforce_policy(&JupiterPolicy {
+            max_slippage_bps,
+            max_amount_in,
+            allow_platform_fee: true,
+        })?;
+        params.validate_accounts(
+            ctx.remaining_accounts,
+            &ExpectedSwapAccounts {
+                source_token: ctx.accounts.swap.user_source_token.key(),
+                source_mint: ctx.accounts.swap.user_source_token.mint,
+                destination_token: ctx.accounts.swap.user_destination_token.key(),
+                destination_mint: ctx.accounts.swap.user_destination_token.mint,
+            },
+        )?;
+        msg!(
+            " {:?}: in {}, quoted out {}, slippage {} bps",
+            params.kind, params.in_amount, params.quoted_out_amount, params.slippage_bps
+        );
+        
+        // Snapshot balances before the swap
+        let snapshot = ctx.accounts.swap.snapshot();
+        
+        // Build Jupiter instruction
+        // ALL accounts come from remaining_accounts
+        let jupiter_ix = passthrough_instruction(
+            JUPITER_V6,
+            ctx.remaining_accounts,
+            jupiter_instruction_data,
+        );
+        
+        // Execute Jupiter CPI
+        invoke_signed(
+            &jupiter_ix,
+            ctx.remaining_accounts,
+            &[], // No PDA signing needed for basic swaps
+        )?;
+        
+        let outcome = ctx.accounts.swap.settle(snapshot, min_amount_out, max_amount_in, RouteKind::Jupiter)?;
+        params.validate_output(outcome.amount_out, max_slippage_bps)?;
+        
+        msg!(
+            " Jupiter swap completed. In: {}, Out: {}, Fee: {}, Net: {}",
+            outcome.amount_in, outcome.amount_out, outcome.fee_amount, outcome.net_amount
+        );
+        msg!(" Total swaps: {}", ctx.accounts.swap.router_state.total_swaps);
+        Ok(())
+    }
+
+    /// Execute a Jupiter `exact_out_route` for exactly `amount_out` of the output
+    /// token, spending at most `max_amount_in`. The input is staged in a
+    /// router-owned escrow and unused input is refunded in the same instruction.
+    /// The Jupiter target is grossed up by the router fee, so exactly
+    /// `amount_out` is left once the fee is skimmed from the output.
+    pub fn execute_exact_out_swap(
+        ctx: Context<ExecuteExactOutSwap>,
+        jupiter_instruction_data: Vec<u8>,
+        amount_out: u64,
+        max_amount_in: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.swap.router_state.paused, ErrorCode::RouterPaused);
+        require!(amount_out > 0 && max_amount_in > 0, ErrorCode::InvalidSwapAmount);
+        
+        let params = decode_jupiter_instruction(&jupiter_instruction_data)?;
+        require!(
+            params.kind == JupiterInstructionKind::ExactOutRoute,
+            ErrorCode::UnsupportedJupiterInstruction
+        );
+        let fee_rate_bps = ctx.accounts.swap.fee_rate_bps(Clock::get()?.unix_timestamp);
+        let gross_amount_out = calculate_gross_amount(amount_out, fee_rate_bps)?;
+        require!(params.quoted_out_amount == gross_amount_out, ErrorCode::ExactOutputMismatch);
+        params.enforce_policy(&JupiterPolicy {
+            max_slippage_bps: ctx.accounts.swap.router_state.max_slippage_bps,
+            max_amount_in: Some(max_amount_in),
+            allow_platform_fee: true,
+        })?;
+        params.validate_accounts(
+            ctx.remaining_accounts,
+            &ExpectedSwapAccounts {
+                source_token: ctx.accounts.escrow.key(),
+                source_mint: ctx.accounts.swap.user_source_token.mint,
+                destination_token: ctx.accounts.swap.user_destination_token.key(),
+                destination_mint: ctx.accounts.swap.user_destination_token.mint,
+            },
+        )?;
+        
+        let snapshot = ctx.accounts.swap.snapshot();
+        ctx.accounts.fund_escrow(max_amount_in)?;
+        
+        // The escrow authority signs as Jupiter's user_transfer_authority
+        let escrow_authority_bump = ctx.bumps.escrow_authority;
+        let seeds = &[b"escrow_authority".as_ref(), &[escrow_authority_bump]];
+        let signer_seeds = &[&seeds[..]];
+        let jupiter_ix = passthrough_instruction_with_signer(
+            JUPITER_V6,
+            ctx.remaining_accounts,
+            jupiter_instruction_data,
+            &ctx.accounts.escrow_authority.key(),
+        );
+        invoke_signed(&jupiter_ix, ctx.remaining_accounts, signer_seeds)?;
+        
+        let refund = ctx.accounts.refund_and_close(escrow_authority_bump)?;
+        
+        // After the refund the source delta is exactly what Jupiter spent
+        let outcome = ctx.accounts.swap.settle(
+            snapshot,
+            None,
+            Some(max_amount_in),
+            RouteKind::JupiterExactOut,
+        )?;
+        require!(
+            outcome.amount_out == gross_amount_out && outcome.net_amount == amount_out,
+            ErrorCode::ExactOutputMismatch
+        );
+        
+        msg!(
+            " Exact-out swap completed. In: {}, Refunded: {}, Out: {}, Fee: {}",
+            outcome.amount_in, refund, outcome.amount_out, outcome.fee_amount
+        );
+        Ok(())
+    }
+
+    /// Create the DEX registry, seeded with Jupiter V6 (authority only)
+    pub fn initialize_dex_registry(ctx: Context<InitializeDexRegistry>) -> Result<()> {
+        let registry = &mut ctx.accounts.dex_registry;
+        registry.entries = vec![DexEntry {
+            program_id: JUPITER_V6,
+            kind: AdapterKind::Jupiter,
+        }];
+        registry.bump = ctx.bumps.dex_registry;
+        
+        msg!(" DEX registry initialized");
+        Ok(())
+    }
+
+    /// Register a DEX program or change its adapter kind (authority only)
+    pub fn add_dex_program(
+        ctx: Context<UpdateDexRegistry>,
+        program_id: Pubkey,
+        kind: AdapterKind,
+    ) -> Result<()> {
+        require!(
+            program_id != Pubkey::default() && program_id != anchor_spl::token::ID,
+            ErrorCode::InvalidDexProgram
+        );
+        
+        ctx.accounts.dex_registry.upsert(program_id, kind)?;
+        
+        emit!(DexProgramUpdated {
+            program_id,
+            kind: Some(kind),
+        });
+        
+        msg!(" DEX program registered: {} ({:?})", program_id, kind);
+        Ok(())
+    }
+
+    /// Remove a DEX program from the registry (authority only)
+    pub fn remove_dex_program(ctx: Context<UpdateDexRegistry>, program_id: Pubkey) -> Result<()> {
+        ctx.accounts.dex_registry.remove(&program_id)?;
+        
+        emit!(DexProgramUpdated {
+            program_id,
+            kind: None,
+        });
+        
+        msg!(" DEX program removed: {}", program_id);
+        Ok(())
+    }
+
+    /// Execute a swap against any DEX program in the registry
+    /// Accounts and instruction data are forwarded unchanged, like execute_jupiter_swap
+    pub fn execute_dex_swap(
+        ctx: Context<ExecuteDexSwap>,
+        dex_instruction_data: Vec<u8>,
+        min_amount_out: Option<u64>,
+        max_amount_in: Option<u64>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.swap.router_state.paused, ErrorCode::RouterPaused);
+        
+        let dex_program = ctx.accounts.dex_program.key();
+        let kind = ctx.accounts.dex_registry.adapter_kind(&dex_program)?;
+        // Jupiter payloads are only forwarded by execute_jupiter_swap, which decodes and checks them
+        require!(kind.is_passthrough(), ErrorCode::InvalidDexProgram);
+        
+        require!(
+            !dex_instruction_data.is_empty(),
+            ErrorCode::EmptyInstructionData
+        );
+        
+        msg!(" Executing {:?} swap via {}", kind, dex_program);
+        
+        let snapshot = ctx.accounts.swap.snapshot();
+        
+        let dex_ix = passthrough_instruction(
+            dex_program,
+            ctx.remaining_accounts,
+            dex_instruction_data,
+        );
+        invoke(&dex_ix, ctx.remaining_accounts)?;
+        
+        let outcome = ctx.accounts.swap.settle(
+            snapshot,
+            min_amount_out,
+            max_amount_in,
+            RouteKind::Dex(kind),
+        )?;
+        
+        msg!(
+            " {:?} swap completed. In: {}, Out: {}, Fee: {}, Net: {}",
+            kind, outcome.amount_in, outcome.amount_out, outcome.fee_amount, outcome.net_amount
+        );
+        Ok(())
+    }
+
+    /// Withdraw accumulated fees from a treasury (authority only)
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidSwapAmount);
+        
+        let router_bump = ctx.accounts.router_state.bump;
+        let seeds = &[b"router_state".as_ref(), &[router_bump]];
+        let signer_seeds = &[&seeds[..]];
+        
+        transfer_tokens(
+            &ctx.accounts.fee_treasury.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.destination.to_account_info(),
+            &ctx.accounts.router_state.to_account_info(),
+            &[&ctx.accounts.token_program.to_account_info()],
+            amount,
+            signer_seeds,
+        )?;
+        
+        msg!(" Withdrew {} fees to {}", amount, ctx.accounts.destination.key());
+        Ok(())
+    }
+
+    /// Register an integrator earning `revenue_share_bps` of the router fee
+    /// on swaps that reference it (authority only)
+    pub fn register_partner(
+        ctx: Context<RegisterPartner>,
+        partner_authority: Pubkey,
+        revenue_share_bps: u16,
+    ) -> Result<()> {
+        require!(
+            revenue_share_bps <= MAX_PARTNER_SHARE_BPS,
+            ErrorCode::InvalidRevenueShare
+        );
+        
+        let partner = &mut ctx.accounts.partner;
+        partner.authority = partner_authority;
+        partner.revenue_share_bps = revenue_share_bps;
+        partner.active = true;
+        partner.swap_count = 0;
+        partner.total_fees_accrued = 0;
+        partner.total_fees_claimed = 0;
+        partner.bump = ctx.bumps.partner;
+        
+        emit!(PartnerUpdated {
+            partner: partner.key(),
+            authority: partner_authority,
+            revenue_share_bps,
+            active: true,
+        });
+        
+        msg!(" Partner registered: {} ({} bps)", partner_authority, revenue_share_bps);
+        Ok(())
+    }
+
+    /// Change a partner's revenue share or (de)activate it (authority only)
+    pub fn update_partner(
+        ctx: Context<UpdatePartner>,
+        revenue_share_bps: u16,
+        active: bool,
+    ) -> Result<()> {
+        require!(
+            revenue_share_bps <= MAX_PARTNER_SHARE_BPS,
+            ErrorCode::InvalidRevenueShare
+        );
+        
+        let partner = &mut ctx.accounts.partner;
+        partner.revenue_share_bps = revenue_share_bps;
+        partner.active = active;
+        
+        emit!(PartnerUpdated {
+            partner: partner.key(),
+            authority: partner.authority,
+            revenue_share_bps,
+            active,
+        });
+        
+        msg!(" Partner updated: {} ({} bps, active: {})", partner.authority, revenue_share_bps, active);
+        Ok(())
+    }
+
+    /// Create a partner's fee account for a given mint
+    pub fn initialize_partner_fee_account(ctx: Context<InitializePartnerFeeAccount>) -> Result<()> {
+        validate_mint(&ctx.accounts.mint.to_account_info())?;
+        msg!(
+            " Partner fee account created for {} / {}",
+            ctx.accounts.partner.authority, ctx.accounts.mint.key()
+        );
+        Ok(())
+    }
+
+    /// Withdraw accrued partner fees (partner authority only)
+    pub fn claim_partner_fees(ctx: Context<ClaimPartnerFees>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidSwapAmount);
+        
+        let partner = &ctx.accounts.partner;
+        let seeds = &[b"partner".as_ref(), partner.authority.as_ref(), &[partner.bump]];
+        let signer_seeds = &[&seeds[..]];
+        
+        transfer_tokens(
+            &ctx.accounts.partner_fee_account.to_account_info(),
+            &ctx.accounts.mint.to_account_info(),
+            &ctx.accounts.destination.to_account_info(),
+            &partner.to_account_info(),
+            &[&ctx.accounts.token_program.to_account_info()],
+            amount,
+            signer_seeds,
+        )?;
+        
+        let partner = &mut ctx.accounts.partner;
+        partner.total_fees_claimed = partner.total_fees_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        emit!(PartnerFeesClaimed {
+            partner: partner.key(),
+            mint: ctx.accounts.partner_fee_account.mint,
+            amount,
+        });
+        
+        msg!(" Partner {} claimed {} fees", partner.authority, amount);
+        Ok(())
+    }
+
+    /// Execute swap using vault authority (for vault-owned funds)
+    /// The vault program must sign for its PDA via CPI; the router never signs for it.
+    /// `vault_seeds` are only used to prove the PDA belongs to a registered vault program.
+    pub fn execute_vault_jupiter_swap(
+        ctx: Context<ExecuteVaultJupiterSwap>,
+        jupiter_instruction_data: Vec<u8>,
+        vault_seeds: Vec<Vec<u8>>,
+    ) -> Result<()> {
+        msg!(" Executing Jupiter swap with vault authority");
+        
+        require!(!ctx.accounts.router_state.paused, ErrorCode::RouterPaused);
+        
+        require!(
+            ctx.accounts.jupiter_program.key() == JUPITER_V6,
+            ErrorCode::InvalidJupiterProgram
+        );
+        
+        // The vault authority must be a PDA of an allow-listed vault program
+        let vault_authority = &ctx.accounts.vault_authority;
+        let vault_program = *vault_authority.owner;
+        require!(
+            ctx.accounts.router_state.is_vault_program(&vault_program),
+            ErrorCode::VaultProgramNotAllowed
+        );
+        
+        let seed_slices: Vec<&[u8]> = vault_seeds.iter().map(|s| s.as_slice()).collect();
+        let derived = Pubkey::create_program_address(&seed_slices, &vault_program)
+            .map_err(|_| ErrorCode::InvalidVaultAuthority)?;
+        require!(
+            derived == vault_authority.key(),
+            ErrorCode::InvalidVaultAuthority
+        );
+        
+        let params = decode_jupiter_instruction(&jupiter_instruction_data)?;
+        params.enforce_policy(&JupiterPolicy {
+            max_slippage_bps: ctx.accounts.router_state.max_slippage_bps,
+            max_amount_in: None,
+            allow_platform_fee: true,
+        })?;
+        params.validate_accounts(
+            ctx.remaining_accounts,
+            &ExpectedSwapAccounts {
+                source_token: ctx.accounts.vault_source_token.key(),
+                source_mint: ctx.accounts.vault_source_token.mint,
+                destination_token: ctx.accounts.vault_destination_token.key(),
+                destination_mint: ctx.accounts.vault_destination_token.mint,
+            },
+        )?;
+        
+        let source_before = ctx.accounts.vault_source_token.amount;
+        let destination_before = ctx.accounts.vault_destination_token.amount;
+        
+        // Build Jupiter instruction
+        let jupiter_ix = passthrough_instruction(
+            JUPITER_V6,
+            ctx.remaining_accounts,
+            jupiter_instruction_data,
+        );
+        
+        // Signer privilege for the vault PDA is inherited from the vault's CPI
+        invoke(&jupiter_ix, ctx.remaining_accounts)?;
+        
+        ctx.accounts.vault_source_token.reload()?;
+        ctx.accounts.vault_destination_token.reload()?;
+        let amount_in = source_before.saturating_sub(ctx.accounts.vault_source_token.amount);
+        let amount_out = ctx.accounts.vault_destination_token.amount.saturating_sub(destination_before);
+        
+        emit!(VaultSwapExecuted {
+            vault_authority: vault_authority.key(),
+            vault_program,
+            input_mint: ctx.accounts.vault_source_token.mint,
+            output_mint: ctx.accounts.vault_destination_token.mint,
+            amount_in,
+            amount_out,
+            route_kind: RouteKind::Jupiter,
+            slot: Clock::get()?.slot,
+        });
+        
+        msg!(" Vault Jupiter swap completed. In: {}, Out: {}", amount_in, amount_out);
+        Ok(())
+    }
+
+    /// Update the router fee rate (authority only)
+    pub fn set_fee_rate(ctx: Context<UpdateRouterConfig>, fee_rate_bps: u16) -> Result<()> {
+        require!(fee_rate_bps <= 1000, ErrorCode::InvalidFeeRate);
+        
+        let router = &mut ctx.accounts.router_state;
+        let old_fee_rate_bps = router.fee_rate_bps;
+        router.fee_rate_bps = fee_rate_bps;
+        
+        emit!(FeeRateUpdated {
+            old_fee_rate_bps,
+            new_fee_rate_bps: fee_rate_bps,
+        });
+        
+        msg!(" Fee rate updated: {} -> {} bps", old_fee_rate_bps, fee_rate_bps);
+        Ok(())
+    }
+
+    /// Cap the slippage_bps accepted in Jupiter payloads (authority only)
+    /// 0 disables the router-side limit
+    pub fn set_max_slippage(ctx: Context<UpdateRouterConfig>, max_slippage_bps: u16) -> Result<()> {
+        require!(max_slippage_bps <= 10_000, ErrorCode::SlippageToleranceTooHigh);
+        
+        let router = &mut ctx.accounts.router_state;
+        let old_max_slippage_bps = router.max_slippage_bps;
+        router.max_slippage_bps = max_slippage_bps;
+        
+        emit!(MaxSlippageUpdated {
+            old_max_slippage_bps,
+            new_max_slippage_bps: max_slippage_bps,
+        });
+        
+        msg!(" Max slippage updated: {} -> {} bps", old_max_slippage_bps, max_slippage_bps);
+        Ok(())
+    }
+
+    /// Replace the volume fee tier table (authority only)
+    /// Tiers need strictly increasing min_volume; an empty list disables discounts
+    pub fn set_fee_tiers(ctx: Context<UpdateRouterConfig>, tiers: Vec<FeeTier>) -> Result<()> {
+        ctx.accounts.router_state.fee_tiers = build_fee_tiers(&tiers)?;
+        
+        emit!(FeeTiersUpdated { tiers: tiers.clone() });
+        
+        msg!(" Fee tiers updated: {} tiers", tiers.len());
+        Ok(())
+    }
+
+    /// Count output in `mint` toward users' volume tiers (authority only).
+    /// Tier thresholds are in raw units, so list quote mints with the same decimals.
+    pub fn register_volume_mint(ctx: Context<UpdateRouterConfig>, mint: Pubkey) -> Result<()> {
+        require!(mint != Pubkey::default(), ErrorCode::TokenMintMismatch);
+        
+        let router = &mut ctx.accounts.router_state;
+        if !router.is_volume_mint(&mint) {
+            let slot = router.volume_mints
+                .iter_mut()
+                .find(|m| **m == Pubkey::default())
+                .ok_or(ErrorCode::VolumeMintListFull)?;
+            *slot = mint;
+        }
+        
+        emit!(VolumeMintUpdated {
+            mint,
+            allowed: true,
+        });
+        
+        msg!(" Volume mint registered: {}", mint);
+        Ok(())
+    }
+
+    /// Stop counting output in `mint` toward volume tiers (authority only)
+    pub fn unregister_volume_mint(ctx: Context<UpdateRouterConfig>, mint: Pubkey) -> Result<()> {
+        let router = &mut ctx.accounts.router_state;
+        for slot in router.volume_mints.iter_mut() {
+            if *slot == mint {
+                *slot = Pubkey::default();
+            }
+        }
+        
+        emit!(VolumeMintUpdated {
+            mint,
+            allowed: false,
+        });
+        
+        msg!(" Volume mint unregistered: {}", mint);
+        Ok(())
+    }
+
+    /// Create the caller's swap stats account (enables volume tiers)
+    pub fn initialize_user_stats(ctx: Context<InitializeUserStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.user_stats;
+        stats.user = ctx.accounts.user.key();
+        stats.swap_count = 0;
+        stats.total_volume = 0;
+        stats.last_day = 0;
+        stats.daily_volume = [0; VOLUME_WINDOW_DAYS];
+        stats.bump = ctx.bumps.user_stats;
+        
+        msg!(" User stats created for: {}", stats.user);
+        Ok(())
+    }
+
+    /// Set the keeper bounty taken from the output of orders keepers fill
+    /// (authority only). Orders keep the bounty in force when placed.
+    pub fn set_keeper_bounty(ctx: Context<UpdateRouterConfig>, keeper_bounty_bps: u16) -> Result<()> {
+        require!(keeper_bounty_bps <= MAX_KEEPER_BOUNTY_BPS, ErrorCode::InvalidKeeperBounty);
+        
+        let router = &mut ctx.accounts.router_state;
+        let old_keeper_bounty_bps = router.keeper_bounty_bps;
+        router.keeper_bounty_bps = keeper_bounty_bps;
+        
+        emit!(KeeperBountyUpdated {
+            old_keeper_bounty_bps,
+            new_keeper_bounty_bps: keeper_bounty_bps,
+        });
+        
+        msg!(" Keeper bounty updated: {} -> {} bps", old_keeper_bounty_bps, keeper_bounty_bps);
+        Ok(())
+    }
+
+    /// Escrow `input_amount` to be sold once the owner can receive at least
+    /// `min_output_amount` (after the router fee and keeper bounty), until
+    /// `expiry_unix_ts`
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrder>,
+        order_id: u64,
+        input_amount: u64,
+        min_output_amount: u64,
+        expiry_unix_ts: i64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.router_state.paused, ErrorCode::RouterPaused);
+        require!(input_amount > 0 && min_output_amount > 0, ErrorCode::InvalidSwapAmount);
+        require!(
+            ctx.accounts.input_mint.key() != ctx.accounts.output_mint.key(),
+            ErrorCode::InvalidTokenPair
+        );
+        require!(expiry_unix_ts > Clock::get()?.unix_timestamp, ErrorCode::OrderExpired);
+        validate_mint(&ctx.accounts.input_mint.to_account_info())?;
+        validate_mint(&ctx.accounts.output_mint.to_account_info())?;
+        
+        transfer_tokens(
+            &ctx.accounts.owner_source_token.to_account_info(),
+            &ctx.accounts.input_mint.to_account_info(),
+            &ctx.accounts.input_escrow.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            &[&ctx.accounts.input_token_program.to_account_info()],
+            input_amount,
+            &[],
+        )?;
+        ctx.accounts.input_escrow.reload()?;
+        let escrowed = ctx.accounts.input_escrow.amount;
+        
+        let order = &mut ctx.accounts.order;
+        order.owner = ctx.accounts.owner.key();
+        order.order_id = order_id;
+        order.input_mint = ctx.accounts.input_mint.key();
+        order.output_mint = ctx.accounts.output_mint.key();
+        order.destination = ctx.accounts.owner_destination_token.key();
+        order.input_amount = escrowed;
+        order.min_output_amount = min_output_amount;
+        order.expiry_unix_ts = expiry_unix_ts;
+        order.keeper_bounty_bps = ctx.accounts.router_state.keeper_bounty_bps;
+        order.bump = ctx.bumps.order;
+        
+        emit!(LimitOrderPlaced {
+            order: order.key(),
+            owner: order.owner,
+            input_mint: order.input_mint,
+            output_mint: order.output_mint,
+            input_amount: escrowed,
+            min_output_amount,
+            expiry_unix_ts,
+        });
+        
+        msg!(" Limit order {} placed: {} in, min {} out", order_id, escrowed, min_output_amount);
+        Ok(())
+    }
+
+    /// Refund a limit order's escrow to its owner and close it
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrder>) -> Result<()> {
+        let order = &ctx.accounts.order;
+        let order_id = order.order_id.to_le_bytes();
+        let seeds = &[b"limit_order".as_ref(), order.owner.as_ref(), order_id.as_ref(), &[order.bump]];
+        let signer_seeds = &[&seeds[..]];
+        let order_info = order.to_account_info();
+        let owner_info = ctx.accounts.owner.to_account_info();
+        
+        let refunded = ctx.accounts.input_escrow.amount;
+        let stray_output = ctx.accounts.output_escrow.amount;
+        for (escrow, mint, to, token_program, amount) in [
+            (
+                ctx.accounts.input_escrow.to_account_info(),
+                ctx.accounts.input_mint.to_account_info(),
+                ctx.accounts.owner_source_token.to_account_info(),
+                ctx.accounts.input_token_program.to_account_info(),
+                refunded,
+            ),
+            (
+                ctx.accounts.output_escrow.to_account_info(),
+                ctx.accounts.output_mint.to_account_info(),
+                ctx.accounts.owner_destination_token.to_account_info(),
+                ctx.accounts.output_token_program.to_account_info(),
+                stray_output,
+            ),
+        ] {
+            if amount > 0 {
+                transfer_tokens(&escrow, &mint, &to, &order_info, &[&token_program], amount, signer_seeds)?;
+            }
+            close_order_escrow(&escrow, &mint, &owner_info, &order_info, &token_program, signer_seeds)?;
+        }
+        
+        emit!(LimitOrderCancelled {
+            order: order.key(),
+            owner: order.owner,
+            refunded,
+        });
+        
+        msg!(" Limit order {} cancelled, refunded {}", order.order_id, refunded);
+        Ok(())
+    }
+
+    /// Fill a limit order through Jupiter (any keeper)
+    /// The whole escrow is sold; the owner must receive at least the order's
+    /// floor after the router fee and the keeper bounty
+    pub fn fill_limit_order(ctx: Context<FillLimitOrder>, jupiter_instruction_data: Vec<u8>) -> Result<()> {
+        require!(!ctx.accounts.router_state.paused, ErrorCode::RouterPaused);
+        require!(
+            Clock::get()?.unix_timestamp <= ctx.accounts.order.expiry_unix_ts,
+            ErrorCode::OrderExpired
+        );
+        
+        // Sell exactly the escrow balance, from and into the order's escrows
+        let amount_in = ctx.accounts.input_escrow.amount;
+        let params = decode_jupiter_instruction(&jupiter_instruction_data)?;
+        require!(
+            params.kind != JupiterInstructionKind::ExactOutRoute,
+            ErrorCode::UnsupportedJupiterInstruction
+        );
+        require!(params.in_amount == amount_in, ErrorCode::InvalidSwapAmount);
+        params.enforce_policy(&JupiterPolicy {
+            max_slippage_bps: ctx.accounts.router_state.max_slippage_bps,
+            max_amount_in: Some(amount_in),
+            // A keeper-chosen platform fee would be paid from the owner's output
+            allow_platform_fee: false,
+        })?;
+        params.validate_accounts(
+            ctx.remaining_accounts,
+            &ExpectedSwapAccounts {
+                source_token: ctx.accounts.input_escrow.key(),
+                source_mint: ctx.accounts.input_mint.key(),
+                destination_token: ctx.accounts.output_escrow.key(),
+                destination_mint: ctx.accounts.output_mint.key(),
+            },
+        )?;
+        
+        let order = &ctx.accounts.order;
+        let order_id = order.order_id.to_le_bytes();
+        let seeds = &[b"limit_order".as_ref(), order.owner.as_ref(), order_id.as_ref(), &[order.bump]];
+        let signer_seeds = &[&seeds[..]];
+        let order_info = order.to_account_info();
+        
+        // The order PDA signs as Jupiter's user_transfer_authority
+        let jupiter_ix = passthrough_instruction_with_signer(
+            JUPITER_V6,
+            ctx.remaining_accounts,
+            jupiter_instruction_data,
+            &order.key(),
+        );
+        invoke_signed(&jupiter_ix, ctx.remaining_accounts, signer_seeds)?;
+        
+        ctx.accounts.input_escrow.reload()?;
+        require!(ctx.accounts.input_escrow.amount == 0, ErrorCode::InvalidSwapAmount);
+        ctx.accounts.output_escrow.reload()?;
+        let amount_out = ctx.accounts.output_escrow.amount;
+        
+        // Keepers choose the route, so fills are held to the oracle guard too
+        OracleGuardAccounts {
+            input_price_feed: ctx.accounts.input_price_feed.as_deref(),
+            input_oracle: ctx.accounts.input_oracle.as_deref(),
+            output_price_feed: ctx.accounts.output_price_feed.as_deref(),
+            output_oracle: ctx.accounts.output_oracle.as_deref(),
+        }
+        .check(&ctx.accounts.router_state, amount_in, amount_out, Clock::get()?.unix_timestamp)?;
+        
+        let output_mint = ctx.accounts.output_mint.to_account_info();
+        let split = KeeperSplit::new(
+            amount_out,
+            ctx.accounts.router_state.fee_rate_bps,
+            order.keeper_bounty_bps,
+        )?;
+        require!(
+            amount_after_transfer_fee(&output_mint, split.user_amount)? >= order.min_output_amount,
+            ErrorCode::InsufficientOutputAmount
+        );
+        
+        let output_escrow = ctx.accounts.output_escrow.to_account_info();
+        let output_token_program = ctx.accounts.output_token_program.to_account_info();
+        let payout = KeeperPayout {
+            source: &output_escrow,
+            mint: &output_mint,
+            authority: &order_info,
+            fee_treasury: &ctx.accounts.fee_treasury.to_account_info(),
+            keeper_token: &ctx.accounts.keeper_token.to_account_info(),
+            user_destination: &ctx.accounts.owner_destination_token.to_account_info(),
+            token_program: &output_token_program,
+        };
+        let fees_collected = payout.pay(&split, signer_seeds)?;
+        
+        let owner_info = ctx.accounts.owner.to_account_info();
+        close_order_escrow(
+            &ctx.accounts.input_escrow.to_account_info(),
+            &ctx.accounts.input_mint.to_account_info(),
+            &owner_info,
+            &order_info,
+            &ctx.accounts.input_token_program.to_account_info(),
+            signer_seeds,
+        )?;
+        close_order_escrow(
+            &output_escrow,
+            &output_mint,
+            &owner_info,
+            &order_info,
+            &output_token_program,
+            signer_seeds,
+        )?;
+        
+        let router = &mut ctx.accounts.router_state;
+        router.total_swaps = router.total_swaps.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        router.total_volume = router.total_volume.saturating_add(amount_in);
+        router.total_fees_collected = router.total_fees_collected
+            .checked_add(fees_collected)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        emit!(LimitOrderFilled {
+            order: order.key(),
+            owner: order.owner,
+            keeper: ctx.accounts.keeper.key(),
+            amount_in,
+            amount_out,
+            fee_amount: fees_collected,
+            keeper_bounty: split.keeper_bounty,
+        });
+        
+        msg!(
+            " Limit order {} filled. In: {}, Out: {}, Fee: {}, Bounty: {}",
+            order.order_id, amount_in, amount_out, fees_collected, split.keeper_bounty
+        );
+        Ok(())
+    }
+
+    /// Escrow `total_amount` to be sold `amount_per_cycle` at a time, at most
+    /// once every `interval_secs`. With `min_output_per_cycle` set, a cycle
+    /// only executes if the owner receives at least that much (pro rata for
+    /// a short last cycle) after the router fee and keeper bounty
+    pub fn create_dca(
+        ctx: Context<CreateDca>,
+        dca_id: u64,
+        total_amount: u64,
+        amount_per_cycle: u64,
+        interval_secs: i64,
+        min_output_per_cycle: u64,
+    ) -> Result<()> {
+        require!(!ctx.accounts.router_state.paused, ErrorCode::RouterPaused);
+        // Keepers choose the route, so every schedule needs a price floor
+        require!(
+            amount_per_cycle > 0
+                && amount_per_cycle <= total_amount
+                && interval_secs > 0
+                && min_output_per_cycle > 0,
+            ErrorCode::InvalidDcaSchedule
+        );
+        require!(
+            ctx.accounts.input_mint.key() != ctx.accounts.output_mint.key(),
+            ErrorCode::InvalidTokenPair
+        );
+        validate_mint(&ctx.accounts.input_mint.to_account_info())?;
+        validate_mint(&ctx.accounts.output_mint.to_account_info())?;
+        
+        transfer_tokens(
+            &ctx.accounts.owner_source_token.to_account_info(),
+            &ctx.accounts.input_mint.to_account_info(),
+            &ctx.accounts.input_escrow.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            &[&ctx.accounts.input_token_program.to_account_info()],
+            total_amount,
+            &[],
+        )?;
+        ctx.accounts.input_escrow.reload()?;
+        let escrowed = ctx.accounts.input_escrow.amount;
+        
+        let dca = &mut ctx.accounts.dca;
+        dca.owner = ctx.accounts.owner.key();
+        dca.dca_id = dca_id;
+        dca.input_mint = ctx.accounts.input_mint.key();
+        dca.output_mint = ctx.accounts.output_mint.key();
+        dca.destination = ctx.accounts.owner_destination_token.key();
+        dca.total_amount = escrowed;
+        dca.amount_per_cycle = amount_per_cycle;
+        dca.interval_secs = interval_secs;
+        dca.min_output_per_cycle = min_output_per_cycle;
+        dca.next_cycle_ts = Clock::get()?.unix_timestamp;
+        dca.cycles_executed = 0;
+        dca.total_spent = 0;
+        dca.total_received = 0;
+        dca.keeper_bounty_bps = ctx.accounts.router_state.keeper_bounty_bps;
+        dca.bump = ctx.bumps.dca;
+        
+        emit!(DcaCreated {
+            dca: dca.key(),
+            owner: dca.owner,
+            input_mint: dca.input_mint,
+            output_mint: dca.output_mint,
+            total_amount: escrowed,
+            amount_per_cycle,
+            interval_secs,
+            min_output_per_cycle,
+        });
+        
+        msg!(" DCA {} created: {} in, {} per cycle every {}s", dca_id, escrowed, amount_per_cycle, interval_secs);
+        Ok(())
+    }
+
+    /// Execute one DCA cycle through Jupiter (any keeper)
+    /// Sells `amount_per_cycle` (or whatever is left) from the escrow once the
+    /// interval has elapsed, crediting the output to the owner
+    pub fn execute_dca_cycle(ctx: Context<ExecuteDcaCycle>, jupiter_instruction_data: Vec<u8>) -> Result<()> {
+        require!(!ctx.accounts.router_state.paused, ErrorCode::RouterPaused);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= ctx.accounts.dca.next_cycle_ts, ErrorCode::DcaCycleNotDue);
+        
+        let escrow_before = ctx.accounts.input_escrow.amount;
+        let amount_in = ctx.accounts.dca.amount_per_cycle.min(escrow_before);
+        require!(amount_in > 0, ErrorCode::DcaCompleted);
+        
+        let params = decode_jupiter_instruction(&jupiter_instruction_data)?;
+        require!(
+            params.kind != JupiterInstructionKind::ExactOutRoute,
+            ErrorCode::UnsupportedJupiterInstruction
+        );
+        require!(params.in_amount == amount_in, ErrorCode::InvalidSwapAmount);
+        params.enforce_policy(&JupiterPolicy {
+            max_slippage_bps: ctx.accounts.router_state.max_slippage_bps,
+            max_amount_in: Some(amount_in),
+            // As for limit order fills, a keeper may not take a platform fee
+            allow_platform_fee: false,
+        })?;
+        params.validate_accounts(
+            ctx.remaining_accounts,
+            &ExpectedSwapAccounts {
+                source_token: ctx.accounts.input_escrow.key(),
+                source_mint: ctx.accounts.input_mint.key(),
+                destination_token: ctx.accounts.output_escrow.key(),
+                destination_mint: ctx.accounts.output_mint.key(),
+            },
+        )?;
+        
+        let dca = &ctx.accounts.dca;
+        let dca_id = dca.dca_id.to_le_bytes();
+        let seeds = &[b"dca".as_ref(), dca.owner.as_ref(), dca_id.as_ref(), &[dca.bump]];
+        let signer_seeds = &[&seeds[..]];
+        let dca_info = dca.to_account_info();
+        
+        // The DCA PDA signs as Jupiter's user_transfer_authority
+        let jupiter_ix = passthrough_instruction_with_signer(
+            JUPITER_V6,
+            ctx.remaining_accounts,
+            jupiter_instruction_data,
+            &dca.key(),
+        );
+        invoke_signed(&jupiter_ix, ctx.remaining_accounts, signer_seeds)?;
+        
+        ctx.accounts.input_escrow.reload()?;
+        let remaining = ctx.accounts.input_escrow.amount;
+        require!(
+            escrow_before.checked_sub(remaining).ok_or(ErrorCode::MathOverflow)? == amount_in,
+            ErrorCode::InvalidSwapAmount
+        );
+        ctx.accounts.output_escrow.reload()?;
+        let amount_out = ctx.accounts.output_escrow.amount;
+        
+        // Keepers choose the route, so fills are held to the oracle guard too
+        OracleGuardAccounts {
+            input_price_feed: ctx.accounts.input_price_feed.as_deref(),
+            input_oracle: ctx.accounts.input_oracle.as_deref(),
+            output_price_feed: ctx.accounts.output_price_feed.as_deref(),
+            output_oracle: ctx.accounts.output_oracle.as_deref(),
+        }
+        .check(&ctx.accounts.router_state, amount_in, amount_out, now)?;
+        
+        let output_mint = ctx.accounts.output_mint.to_account_info();
+        let split = KeeperSplit::new(
+            amount_out,
+            ctx.accounts.router_state.fee_rate_bps,
+            dca.keeper_bounty_bps,
+        )?;
+        let received = amount_after_transfer_fee(&output_mint, split.user_amount)?;
+        require!(received >= dca.min_output_for(amount_in)?, ErrorCode::InsufficientOutputAmount);
+        
+        let output_escrow = ctx.accounts.output_escrow.to_account_info();
+        let payout = KeeperPayout {
+            source: &output_escrow,
+            mint: &output_mint,
+            authority: &dca_info,
+            fee_treasury: &ctx.accounts.fee_treasury.to_account_info(),
+            keeper_token: &ctx.accounts.keeper_token.to_account_info(),
+            user_destination: &ctx.accounts.owner_destination_token.to_account_info(),
+            token_program: &ctx.accounts.output_token_program.to_account_info(),
+        };
+        let fees_collected = payout.pay(&split, signer_seeds)?;
+        
+        let dca = &mut ctx.accounts.dca;
+        dca.next_cycle_ts = now.checked_add(dca.interval_secs).ok_or(ErrorCode::MathOverflow)?;
+        dca.cycles_executed = dca.cycles_executed.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        dca.total_spent = dca.total_spent.checked_add(amount_in).ok_or(ErrorCode::MathOverflow)?;
+        dca.total_received = dca.total_received.saturating_add(received);
+        
+        let router = &mut ctx.accounts.router_state;
+        router.total_swaps = router.total_swaps.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        router.total_volume = router.total_volume.saturating_add(amount_in);
+        router.total_fees_collected = router.total_fees_collected
+            .checked_add(fees_collected)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        emit!(DcaCycleExecuted {
+            dca: dca.key(),
+            owner: dca.owner,
+            keeper: ctx.accounts.keeper.key(),
+            cycle: dca.cycles_executed,
+            amount_in,
+            amount_out,
+            fee_amount: fees_collected,
+            keeper_bounty: split.keeper_bounty,
+            remaining,
+        });
+        
+        msg!(
+            " DCA {} cycle {} executed. In: {}, Out: {}, Fee: {}, Bounty: {}, Remaining: {}",
+            dca.dca_id, dca.cycles_executed, amount_in, amount_out, fees_collected, split.keeper_bounty, remaining
+        );
+        Ok(())
+    }
+
+    /// Stop a DCA schedule: refund the unspent input to its owner and close it
+    pub fn close_dca(ctx: Context<CloseDca>) -> Result<()> {
+        let dca = &ctx.accounts.dca;
+        let dca_id = dca.dca_id.to_le_bytes();
+        let seeds = &[b"dca".as_ref(), dca.owner.as_ref(), dca_id.as_ref(), &[dca.bump]];
+        let signer_seeds = &[&seeds[..]];
+        let dca_info = dca.to_account_info();
+        let owner_info = ctx.accounts.owner.to_account_info();
+        
+        let refunded = ctx.accounts.input_escrow.amount;
+        let stray_output = ctx.accounts.output_escrow.amount;
+        for (escrow, mint, to, token_program, amount) in [
+            (
+                ctx.accounts.input_escrow.to_account_info(),
+                ctx.accounts.input_mint.to_account_info(),
+                ctx.accounts.owner_source_token.to_account_info(),
+                ctx.accounts.input_token_program.to_account_info(),
+                refunded,
+            ),
+            (
+                ctx.accounts.output_escrow.to_account_info(),
+                ctx.accounts.output_mint.to_account_info(),
+                ctx.accounts.owner_destination_token.to_account_info(),
+                ctx.accounts.output_token_program.to_account_info(),
+                stray_output,
+            ),
+        ] {
+            if amount > 0 {
+                transfer_tokens(&escrow, &mint, &to, &dca_info, &[&token_program], amount, signer_seeds)?;
+            }
+            close_order_escrow(&escrow, &mint, &owner_info, &dca_info, &token_program, signer_seeds)?;
+        }
+        
+        emit!(DcaClosed {
+            dca: dca.key(),
+            owner: dca.owner,
+            refunded,
+            cycles_executed: dca.cycles_executed,
+        });
+        
+        msg!(" DCA {} closed after {} cycles, refunded {}", dca.dca_id, dca.cycles_executed, refunded);
+        Ok(())
+    }
+
+    /// Configure the oracle price guard (authority only)
+    /// 0 bps disables it; otherwise swaps passing price feeds must execute
+    /// within `max_price_deviation_bps` of the oracle price
+    pub fn set_oracle_guard(
+        ctx: Context<UpdateRouterConfig>,
+        max_price_deviation_bps: u16,
+        max_oracle_staleness_secs: u32,
+    ) -> Result<()> {
+        require!(
+            max_price_deviation_bps == 0
+                || (max_price_deviation_bps <= 10_000 && max_oracle_staleness_secs > 0),
+            ErrorCode::InvalidOracleConfig
+        );
+        
+        let router = &mut ctx.accounts.router_state;
+        router.max_price_deviation_bps = max_price_deviation_bps;
+        router.max_oracle_staleness_secs = max_oracle_staleness_secs;
+        
+        emit!(OracleGuardUpdated {
+            max_price_deviation_bps,
+            max_oracle_staleness_secs,
+        });
+        
+        msg!(" Oracle guard updated: {} bps, {}s staleness", max_price_deviation_bps, max_oracle_staleness_secs);
+        Ok(())
+    }
+
+    /// Register the oracle pricing `mint` (authority only)
+    /// Push feeds must point at the mint's PushOracle PDA
+    pub fn register_price_feed(
+        ctx: Context<RegisterPriceFeed>,
+        kind: OracleKind,
+        oracle: Pubkey,
+        feed_id: [u8; 32],
     ) -> Result<()> {
-        require!(fee_rate_bps <= 1000, ErrorCode::InvalidFeeRate);
+        let mint = ctx.accounts.mint.key();
+        validate_price_feed_source(&mint, kind, &oracle, &feed_id)?;
+        
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.mint = mint;
+        price_feed.decimals = ctx.accounts.mint.decimals;
+        price_feed.kind = kind;
+        price_feed.oracle = oracle;
+        price_feed.feed_id = feed_id;
+        price_feed.bump = ctx.bumps.price_feed;
+        
+        emit!(PriceFeedUpdated { mint, kind, oracle });
+        
+        msg!(" Price feed registered for {}: {:?} {}", mint, kind, oracle);
+        Ok(())
+    }
+
+    /// Point an existing price feed at another oracle (authority only)
+    pub fn update_price_feed(
+        ctx: Context<UpdatePriceFeed>,
+        kind: OracleKind,
+        oracle: Pubkey,
+        feed_id: [u8; 32],
+    ) -> Result<()> {
+        let price_feed = &mut ctx.accounts.price_feed;
+        validate_price_feed_source(&price_feed.mint, kind, &oracle, &feed_id)?;
+        
+        price_feed.kind = kind;
+        price_feed.oracle = oracle;
+        price_feed.feed_id = feed_id;
+        
+        emit!(PriceFeedUpdated {
+            mint: price_feed.mint,
+            kind,
+            oracle,
+        });
         
+        msg!(" Price feed updated for {}: {:?} {}", price_feed.mint, kind, oracle);
+        Ok(())
+    }
+
+    /// Create the router-owned push oracle for `mint` (authority only)
+    pub fn initialize_push_oracle(ctx: Context<InitializePushOracle>, pusher: Pubkey) -> Result<()> {
+        let push_oracle = &mut ctx.accounts.push_oracle;
+        push_oracle.mint = ctx.accounts.mint.key();
+        push_oracle.pusher = pusher;
+        push_oracle.price = 0;
+        push_oracle.expo = 0;
+        push_oracle.publish_time = 0;
+        push_oracle.bump = ctx.bumps.push_oracle;
+        
+        msg!(" Push oracle created for {} (pusher: {})", push_oracle.mint, pusher);
+        Ok(())
+    }
+
+    /// Publish a price (price * 10^expo per whole token) to a push oracle
+    pub fn push_price(ctx: Context<PushPrice>, price: i64, expo: i32) -> Result<()> {
+        require!(price > 0, ErrorCode::InvalidOracleConfig);
+        
+        let push_oracle = &mut ctx.accounts.push_oracle;
+        push_oracle.price = price;
+        push_oracle.expo = expo;
+        push_oracle.publish_time = Clock::get()?.unix_timestamp;
+        
+        msg!(" Price pushed for {}: {}e{}", push_oracle.mint, price, expo);
+        Ok(())
+    }
+
+    /// Propose a new authority (step 1 of 2)
+    /// Proposing Pubkey::default() cancels a pending transfer
+    pub fn propose_authority(ctx: Context<UpdateRouterConfig>, new_authority: Pubkey) -> Result<()> {
         let router = &mut ctx.accounts.router_state;
-        router.authority = ctx.accounts.authority.key();
-        router.fee_rate_bps = fee_rate_bps;
-        router.total_swaps = 0;
-        router.total_volume = 0;
-        router.bump = ctx.bumps.router_state;
+        router.pending_authority = new_authority;
         
-        msg!(" Router initialized with fee: {} bps", fee_rate_bps);
+        emit!(AuthorityProposed {
+            authority: router.authority,
+            pending_authority: new_authority,
+        });
+        
+        msg!(" Authority transfer proposed to: {}", new_authority);
         Ok(())
     }
 
-    /// Execute a swap via Jupiter aggregator
-    /// All accounts and instruction data come from Jupiter API
-    pub fn execute_jupiter_swap(
-        ctx: Context<ExecuteJupiterSwap>,
-        jupiter_instruction_data: Vec<u8>,
+    /// Accept a pending authority transfer (step 2 of 2)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let router = &mut ctx.accounts.router_state;
+        let old_authority = router.authority;
+        router.authority = ctx.accounts.new_authority.key();
+        router.pending_authority = Pubkey::default();
+        
+        emit!(AuthorityTransferred {
+            old_authority,
+            new_authority: router.authority,
+        });
+        
+        msg!(" Authority transferred: {} -> {}", old_authority, router.authority);
+        Ok(())
+    }
+
+    /// Pause or unpause swap execution (authority only)
+    pub fn set_paused(ctx: Context<UpdateRouterConfig>, paused: bool) -> Result<()> {
+        let router = &mut ctx.accounts.router_state;
+        router.paused = paused;
+        
+        emit!(PauseUpdated { paused });
+        
+        msg!(" Router paused: {}", paused);
+        Ok(())
+    }
+
+    /// Allow a vault program to use execute_vault_jupiter_swap (authority only)
+    pub fn register_vault_program(ctx: Context<UpdateRouterConfig>, program_id: Pubkey) -> Result<()> {
+        require!(program_id != Pubkey::default(), ErrorCode::InvalidVaultAuthority);
+        
+        let router = &mut ctx.accounts.router_state;
+        if !router.is_vault_program(&program_id) {
+            let slot = router.vault_programs
+                .iter_mut()
+                .find(|p| **p == Pubkey::default())
+                .ok_or(ErrorCode::VaultProgramListFull)?;
+            *slot = program_id;
+        }
+        
+        emit!(VaultProgramUpdated {
+            program_id,
+            allowed: true,
+        });
+        
+        msg!(" Vault program registered: {}", program_id);
+        Ok(())
+    }
+
+    /// Remove a vault program from the allow-list (authority only)
+    pub fn unregister_vault_program(ctx: Context<UpdateRouterConfig>, program_id: Pubkey) -> Result<()> {
+        let router = &mut ctx.accounts.router_state;
+        for slot in router.vault_programs.iter_mut() {
+            if *slot == program_id {
+                *slot = Pubkey::default();
+            }
+        }
+        
+        emit!(VaultProgramUpdated {
+            program_id,
+            allowed: false,
+        });
+        
+        msg!(" Vault program unregistered: {}", program_id);
+        Ok(())
+    }
+
+    /// Grow a router_state created by an older program version to the current layout.
+    /// New fields are appended after `bump` and start zeroed.
+    pub fn migrate_router_state(ctx: Context<MigrateRouterState>) -> Result<()> {
+        let router_info = ctx.accounts.router_state.to_account_info();
+        let new_len = 8 + RouterState::INIT_SPACE;
+        
+        {
+            let data = router_info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 + RouterState::LEGACY_SPACE
+                    && data[..8] == *RouterState::DISCRIMINATOR,
+                ErrorCode::RouterNotInitialized
+            );
+            // authority is the first field in every layout
+            let authority = Pubkey::try_from(&data[8..40])
+                .map_err(|_| ErrorCode::RouterNotInitialized)?;
+            require!(
+                authority == ctx.accounts.authority.key(),
+                ErrorCode::Unauthorized
+            );
+            
+            if data.len() >= new_len {
+                msg!(" Router state already at current layout");
+                return Ok(());
+            }
+        }
+        
+        // Top up rent for the larger account
+        let required_lamports = Rent::get()?.minimum_balance(new_len);
+        let current_lamports = router_info.lamports();
+        if required_lamports > current_lamports {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: router_info.clone(),
+                    },
+                ),
+                required_lamports - current_lamports,
+            )?;
+        }
+        
+        router_info.resize(new_len)?;
+        
+        msg!(" Router state migrated to {} bytes", new_len);
+        Ok(())
+    }
+
+    /// Create an empty x*y=k pool for a mint pair (mint_a < mint_b)
+    /// The first add_liquidity sets the price
+    pub fn create_pool(ctx: Context<CreatePool>) -> Result<()> {
+        validate_mint(&ctx.accounts.mint_a.to_account_info())?;
+        validate_mint(&ctx.accounts.mint_b.to_account_info())?;
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.mint_a = ctx.accounts.mint_a.key();
+        pool.mint_b = ctx.accounts.mint_b.key();
+        pool.vault_a = ctx.accounts.vault_a.key();
+        pool.vault_b = ctx.accounts.vault_b.key();
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.lp_supply = 0;
+        pool.bump = ctx.bumps.pool;
+        
+        emit!(PoolCreated {
+            pool: pool.key(),
+            mint_a: pool.mint_a,
+            mint_b: pool.mint_b,
+            lp_mint: pool.lp_mint,
+        });
+        
+        msg!(" Pool created: {} / {}", pool.mint_a, pool.mint_b);
+        Ok(())
+    }
+
+    /// Deposit both tokens at the pool ratio and mint LP tokens
+    pub fn add_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        max_amount_a: u64,
+        max_amount_b: u64,
+        min_lp_out: u64,
     ) -> Result<()> {
-        msg!(" Executing Jupiter swap");
+        require!(max_amount_a > 0 && max_amount_b > 0, ErrorCode::InvalidSwapAmount);
         
-        // Validate Jupiter program
+        let reserve_a = ctx.accounts.vault_a.amount;
+        let reserve_b = ctx.accounts.vault_b.amount;
+        let lp_supply = ctx.accounts.pool.lp_supply;
+        
+        let mint_a = ctx.accounts.mint_a.to_account_info();
+        let mint_b = ctx.accounts.mint_b.to_account_info();
+        
+        let (amount_a, amount_b) = if lp_supply == 0 {
+            (max_amount_a, max_amount_b)
+        } else {
+            let (amount_a, amount_b, _) =
+                calculate_deposit(max_amount_a, max_amount_b, reserve_a, reserve_b, lp_supply)?;
+            (amount_a, amount_b)
+        };
+        
+        // LP is minted for what reaches the vaults after any transfer fee
+        let received_a = amount_after_transfer_fee(&mint_a, amount_a)?;
+        let received_b = amount_after_transfer_fee(&mint_b, amount_b)?;
+        let (lp_out, new_lp_supply) = if lp_supply == 0 {
+            // First deposit: LP = sqrt(a * b), minus the locked minimum
+            let liquidity = integer_sqrt(received_a as u128 * received_b as u128) as u64;
+            require!(liquidity > MINIMUM_LIQUIDITY, ErrorCode::InsufficientLiquidity);
+            (liquidity - MINIMUM_LIQUIDITY, liquidity)
+        } else {
+            let (_, _, lp_out) =
+                calculate_deposit(received_a, received_b, reserve_a, reserve_b, lp_supply)?;
+            (lp_out, lp_supply.checked_add(lp_out).ok_or(ErrorCode::MathOverflow)?)
+        };
+        
+        require!(lp_out >= min_lp_out, ErrorCode::SlippageExceeded);
+        
+        let programs = [
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.token_2022_program.to_account_info(),
+        ];
+        for (from, mint, to, amount) in [
+            (&ctx.accounts.user_token_a, &mint_a, &ctx.accounts.vault_a, amount_a),
+            (&ctx.accounts.user_token_b, &mint_b, &ctx.accounts.vault_b, amount_b),
+        ] {
+            transfer_tokens(
+                &from.to_account_info(),
+                mint,
+                &to.to_account_info(),
+                &ctx.accounts.user.to_account_info(),
+                &programs,
+                amount,
+                &[],
+            )?;
+        }
+        
+        let pool = &ctx.accounts.pool;
+        let seeds = &[b"pool".as_ref(), pool.mint_a.as_ref(), pool.mint_b.as_ref(), &[pool.bump]];
+        let signer_seeds = &[&seeds[..]];
+        
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.user_lp_token.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            lp_out,
+        )?;
+        
+        ctx.accounts.pool.lp_supply = new_lp_supply;
+        
+        emit!(LiquidityAdded {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount_a,
+            amount_b,
+            lp_amount: lp_out,
+        });
+        
+        msg!(" Liquidity added: {} A, {} B, {} LP", amount_a, amount_b, lp_out);
+        Ok(())
+    }
+
+    /// Burn LP tokens and withdraw the proportional share of both reserves
+    pub fn remove_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        lp_amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        require!(lp_amount > 0, ErrorCode::InvalidSwapAmount);
+        
+        let lp_supply = ctx.accounts.pool.lp_supply;
+        let amount_a = calculate_withdrawal(lp_amount, ctx.accounts.vault_a.amount, lp_supply)?;
+        let amount_b = calculate_withdrawal(lp_amount, ctx.accounts.vault_b.amount, lp_supply)?;
+        
+        // Minimums apply to what the user receives after any transfer fee
+        let mint_a = ctx.accounts.mint_a.to_account_info();
+        let mint_b = ctx.accounts.mint_b.to_account_info();
         require!(
-            ctx.accounts.jupiter_program.key() == JUPITER_V6,
-            ErrorCode::InvalidJupiterProgram
+            amount_after_transfer_fee(&mint_a, amount_a)? >= min_amount_a,
+            ErrorCode::SlippageExceeded
         );
-        
         require!(
-            !jupiter_instruction_data.is_empty(),
-            ErrorCode::EmptyInstructionData
+            amount_after_transfer_fee(&mint_b, amount_b)? >= min_amount_b,
+            ErrorCode::SlippageExceeded
         );
         
-        // Update router stats
-        let router = &mut ctx.accounts.router_state;
-        router.total_swaps = router.total_swaps.checked_add(1).unwrap();
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.user_lp_token.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            lp_amount,
+        )?;
         
-        // Build Jupiter instruction
-        // ALL accounts come from remaining_accounts
-        let jupiter_ix = Instruction {
-            program_id: JUPITER_V6,
-            accounts: ctx.remaining_accounts
-                .iter()
-                .map(|acc| AccountMeta {
-                    pubkey: acc.key(),
-                    is_signer: acc.is_signer,
-                    is_writable: acc.is_writable,
-                })
-                .collect(),
-            data: jupiter_instruction_data,
+        let pool = &ctx.accounts.pool;
+        let seeds = &[b"pool".as_ref(), pool.mint_a.as_ref(), pool.mint_b.as_ref(), &[pool.bump]];
+        let signer_seeds = &[&seeds[..]];
+        
+        let programs = [
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.token_2022_program.to_account_info(),
+        ];
+        for (from, mint, to, amount) in [
+            (&ctx.accounts.vault_a, &mint_a, &ctx.accounts.user_token_a, amount_a),
+            (&ctx.accounts.vault_b, &mint_b, &ctx.accounts.user_token_b, amount_b),
+        ] {
+            transfer_tokens(
+                &from.to_account_info(),
+                mint,
+                &to.to_account_info(),
+                &pool.to_account_info(),
+                &programs,
+                amount,
+                signer_seeds,
+            )?;
+        }
+        
+        ctx.accounts.pool.lp_supply = lp_supply
+            .checked_sub(lp_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        
+        emit!(LiquidityRemoved {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount_a,
+            amount_b,
+            lp_amount,
+        });
+        
+        msg!(" Liquidity removed: {} A, {} B, {} LP", amount_a, amount_b, lp_amount);
+        Ok(())
+    }
+
+    /// Swap against a native pool. Direction follows the user's token accounts.
+    /// The router fee is taken from the output like any other router swap.
+    pub fn swap(ctx: Context<PoolSwap>, amount_in: u64, min_amount_out: u64) -> Result<()> {
+        require!(!ctx.accounts.swap.router_state.paused, ErrorCode::RouterPaused);
+        require!(amount_in > 0, ErrorCode::InvalidSwapAmount);
+        
+        let snapshot = ctx.accounts.swap.snapshot();
+        
+        let leg = PoolLeg {
+            pool: &ctx.accounts.pool,
+            vault_a: &ctx.accounts.vault_a.to_account_info(),
+            vault_b: &ctx.accounts.vault_b.to_account_info(),
+            mint_a: &ctx.accounts.mint_a.to_account_info(),
+            mint_b: &ctx.accounts.mint_b.to_account_info(),
         };
+        let result = leg.swap(
+            &ctx.accounts.swap.user_source_token.to_account_info(),
+            &ctx.accounts.swap.user_destination_token.to_account_info(),
+            &ctx.accounts.swap.user.to_account_info(),
+            &[
+                &ctx.accounts.swap.token_program.to_account_info(),
+                &ctx.accounts.swap.token_2022_program.to_account_info(),
+            ],
+            amount_in,
+        )?;
         
-        // Execute Jupiter CPI
-        invoke_signed(
-            &jupiter_ix,
-            ctx.remaining_accounts,
-            &[], // No PDA signing needed for basic swaps
+        let outcome = ctx.accounts.swap.settle(
+            snapshot,
+            Some(min_amount_out),
+            Some(amount_in),
+            RouteKind::NativePool,
         )?;
         
-        msg!(" Jupiter swap completed. Total swaps: {}", router.total_swaps);
+        msg!(
+            " Pool swap completed. In: {}, Out: {}, Fee: {}, Impact: {} bps",
+            outcome.amount_in, outcome.amount_out, outcome.fee_amount, result.price_impact_bps
+        );
         Ok(())
     }
 
-    /// Execute swap using vault authority (for vault-owned funds)
-    pub fn execute_vault_jupiter_swap(
-        ctx: Context<ExecuteVaultJupiterSwap>,
-        jupiter_instruction_data: Vec<u8>,
-        vault_seeds: Vec<Vec<u8>>,
+    /// Execute a typed multi-hop route in one instruction.
+    /// Hops run in order with balance checks between them; the router fee is
+    /// charged once on the final output.
+    pub fn execute_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteRoute<'info>>,
+        hops: Vec<RouteHop>,
+        min_amount_out: Option<u64>,
     ) -> Result<()> {
-        msg!(" Executing Jupiter swap with vault authority");
+        require!(!ctx.accounts.swap.router_state.paused, ErrorCode::RouterPaused);
         
+        let result = ctx.accounts.execute_hops(&hops, ctx.remaining_accounts)?;
+        
+        // The first hop's exact amount already caps the input
+        let outcome = ctx.accounts.swap.finalize(
+            result.amount_in,
+            result.amount_out,
+            min_amount_out,
+            None,
+            RouteKind::MultiHop { hops: hops.len() as u8 },
+        )?;
+        
+        msg!(
+            " Route completed ({} hops). In: {}, Out: {}, Fee: {}",
+            hops.len(), outcome.amount_in, outcome.amount_out, outcome.fee_amount
+        );
+        Ok(())
+    }
+
+    /// Execute a round-trip route and revert unless it ends with more of the
+    /// starting token than it began with. The router fee is charged on the
+    /// profit; `min_profit` applies to what is left after it.
+    pub fn execute_arbitrage_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteRoute<'info>>,
+        hops: Vec<RouteHop>,
+        min_profit: u64,
+    ) -> Result<()> {
+        let swap = &ctx.accounts.swap;
+        require!(!swap.router_state.paused, ErrorCode::RouterPaused);
+        require!(hops.len() >= 2, ErrorCode::InvalidRoute);
         require!(
-            ctx.accounts.jupiter_program.key() == JUPITER_V6,
-            ErrorCode::InvalidJupiterProgram
+            swap.user_source_token.key() == swap.user_destination_token.key(),
+            ErrorCode::InvalidRoute
+        );
+        // Same-mint legs cannot be priced against the oracles
+        require!(
+            swap.input_price_feed.is_none() && swap.output_price_feed.is_none(),
+            ErrorCode::InvalidOracleAccount
         );
         
-        // Build Jupiter instruction
-        let jupiter_ix = Instruction {
-            program_id: JUPITER_V6,
-            accounts: ctx.remaining_accounts
-                .iter()
-                .map(|acc| AccountMeta {
-                    pubkey: acc.key(),
-                    is_signer: acc.is_signer,
-                    is_writable: acc.is_writable,
-                })
-                .collect(),
-            data: jupiter_instruction_data,
-        };
+        let start_balance = swap.user_source_token.amount;
+        let result = ctx.accounts.execute_hops(&hops, ctx.remaining_accounts)?;
         
-        // Convert vault_seeds to proper format
-        let seed_slices: Vec<&[u8]> = vault_seeds.iter().map(|s| s.as_slice()).collect();
-        let signer_seeds = &[seed_slices.as_slice()];
+        ctx.accounts.swap.user_source_token.reload()?;
+        ctx.accounts.swap.user_destination_token.reload()?;
+        let final_balance = ctx.accounts.swap.user_source_token.amount;
+        let profit = final_balance
+            .checked_sub(start_balance)
+            .filter(|profit| *profit > 0)
+            .ok_or(ErrorCode::InsufficientProfit)?;
         
-        // Execute with vault PDA signing
-        invoke_signed(&jupiter_ix, ctx.remaining_accounts, signer_seeds)?;
+        let outcome = ctx.accounts.swap.finalize(
+            result.amount_in,
+            profit,
+            None,
+            None,
+            RouteKind::Arbitrage { hops: hops.len() as u8 },
+        )?;
+        require!(outcome.net_amount >= min_profit, ErrorCode::InsufficientProfit);
         
-        msg!(" Vault Jupiter swap completed");
+        msg!(
+            " Arbitrage route completed ({} hops). In: {}, Out: {}, Profit: {}, Fee: {}",
+            hops.len(), outcome.amount_in, result.amount_out, outcome.net_amount, outcome.fee_amount
+        );
         Ok(())
     }
 
@@ -130,6 +1675,8 @@ pub mod swap_router {
             fee_rate_bps: router.fee_rate_bps,
             total_swaps: router.total_swaps,
             total_volume: router.total_volume,
+            total_fees_collected: router.total_fees_collected,
+            paused: router.paused,
         })
     }
 }
@@ -154,17 +1701,53 @@ pub struct InitializeRouter<'info> {
 }
 
 #[derive(Accounts)]
-pub struct ExecuteJupiterSwap<'info> {
+pub struct InitializeFeeTreasury<'info> {
+    #[account(seeds = [b"router_state"], bump = router_state.bump)]
+    pub router_state: Account<'info, RouterState>,
+    
     #[account(
-        mut,
-        seeds = [b"router_state"],
-        bump = router_state.bump
+        init,
+        payer = payer,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = router_state,
+        token::token_program = token_program
     )]
-    pub router_state: Account<'info, RouterState>,
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(address = *mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMintStats<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MintStats::INIT_SPACE,
+        seeds = [b"mint_stats", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
     
-    /// User executing the swap
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteJupiterSwap<'info> {
+    pub swap: SwapAccounts<'info>,
     
     /// CHECK: Jupiter V6 Program - validated in instruction
     #[account(constraint = jupiter_program.key() == JUPITER_V6)]
@@ -175,6 +1758,43 @@ pub struct ExecuteJupiterSwap<'info> {
     // Jupiter API tells  you which accounts to include
 }
 
+#[derive(Accounts)]
+pub struct ExecuteDexSwap<'info> {
+    pub swap: SwapAccounts<'info>,
+    
+    #[account(seeds = [b"dex_registry"], bump = dex_registry.bump)]
+    pub dex_registry: Account<'info, DexRegistry>,
+    
+    /// CHECK: Validated against the DEX registry in instruction
+    pub dex_program: AccountInfo<'info>,
+    
+    // Accounts for the target DEX are passed via remaining_accounts
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(seeds = [b"router_state"], bump = router_state.bump, has_one = authority)]
+    pub router_state: Account<'info, RouterState>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", fee_treasury.mint.as_ref()],
+        bump
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = fee_treasury.mint @ ErrorCode::TokenMintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut, token::mint = fee_treasury.mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    #[account(address = *mint.to_account_info().owner @ ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
 #[derive(Accounts)]
 pub struct ExecuteVaultJupiterSwap<'info> {
     #[account(
@@ -184,15 +1804,67 @@ pub struct ExecuteVaultJupiterSwap<'info> {
     )]
     pub router_state: Account<'info, RouterState>,
     
-    /// Vault authority (PDA)
-    /// CHECK: Validated by vault program
-    pub vault_authority: AccountInfo<'info>,
+    /// Vault authority PDA, signed for by the vault program via CPI.
+    /// Owner and derivation are checked against the registered vault programs.
+    pub vault_authority: Signer<'info>,
+    
+    /// Vault token account spent by the swap
+    #[account(mut, token::authority = vault_authority)]
+    pub vault_source_token: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Vault token account receiving the output (same as the source for round trips)
+    #[account(mut, token::authority = vault_authority)]
+    pub vault_destination_token: InterfaceAccount<'info, TokenAccount>,
     
     /// CHECK: Jupiter V6 Program
     #[account(constraint = jupiter_program.key() == JUPITER_V6)]
     pub jupiter_program: AccountInfo<'info>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateRouterConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"router_state"],
+        bump = router_state.bump,
+        constraint = router_state.pending_authority == new_authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub router_state: Account<'info, RouterState>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRouterState<'info> {
+    /// CHECK: May still use an older layout, so it is read manually.
+    /// Address and owner are enforced here; discriminator and authority in the handler.
+    #[account(
+        mut,
+        seeds = [b"router_state"],
+        bump,
+        owner = crate::ID
+    )]
+    pub router_state: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct GetStats<'info> {
     #[account(seeds = [b"router_state"], bump = router_state.bump)]
@@ -208,10 +1880,71 @@ pub struct RouterState {
     pub total_swaps: u64,       // 8
     pub total_volume: u64,      // 8
     pub bump: u8,               // 1
+    // Fields below were added after the initial deployment.
+    // Always append new fields here so migrate_router_state can zero-extend.
+    pub total_fees_collected: u64, // 8
+    pub pending_authority: Pubkey, // 32 (default = no transfer pending)
+    pub paused: bool,           // 1
+    pub vault_programs: [Pubkey; MAX_VAULT_PROGRAMS], // 32 * 4 (default = empty slot)
+    pub max_slippage_bps: u16,  // 2  (Jupiter slippage cap, 0 = no limit)
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS], // 10 * 4 (volume discounts, empty slot = min_volume 0)
+    pub max_price_deviation_bps: u16, // 2 (oracle guard, 0 = off)
+    pub max_oracle_staleness_secs: u32, // 4
+    pub keeper_bounty_bps: u16, // 2  (paid to keepers filling orders)
+    pub volume_mints: [Pubkey; MAX_VOLUME_MINTS], // 32 * 4 (quote mints counted toward fee tiers, default = empty slot)
 }
 
 impl RouterState {
-    pub const INIT_SPACE: usize = 32 + 2 + 8 + 8 + 1;
+    /// Size of the layout shipped in the first deployment
+    pub const LEGACY_SPACE: usize = 32 + 2 + 8 + 8 + 1;
+    pub const INIT_SPACE: usize = Self::LEGACY_SPACE + 8 + 32 + 1 + 32 * MAX_VAULT_PROGRAMS + 2
+        + FeeTier::INIT_SPACE * MAX_FEE_TIERS + 2 + 4 + 2 + 32 * MAX_VOLUME_MINTS;
+
+    pub fn is_vault_program(&self, program_id: &Pubkey) -> bool {
+        *program_id != Pubkey::default() && self.vault_programs.contains(program_id)
+    }
+
+    pub fn is_volume_mint(&self, mint: &Pubkey) -> bool {
+        *mint != Pubkey::default() && self.volume_mints.contains(mint)
+    }
+}
+
+/// Per-mint swap statistics (seeds: ["mint_stats", mint])
+#[account]
+pub struct MintStats {
+    pub mint: Pubkey,           // 32
+    pub swap_count: u64,        // 8
+    pub volume_in: u128,        // 16 (amount sold into swaps)
+    pub volume_out: u128,       // 16 (amount bought out of swaps, gross of fees)
+    pub fees_collected: u64,    // 8
+    pub bump: u8,               // 1
+}
+
+impl MintStats {
+    pub const INIT_SPACE: usize = 32 + 8 + 16 + 16 + 8 + 1;
+
+    /// Count a swap touching this mint; call once per swap, even for round trips
+    pub fn record_swap(&mut self) -> Result<()> {
+        self.swap_count = self.swap_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_input(&mut self, amount: u64) -> Result<()> {
+        self.volume_in = self.volume_in
+            .checked_add(amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_output(&mut self, amount: u64, fee: u64) -> Result<()> {
+        self.volume_out = self.volume_out
+            .checked_add(amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.fees_collected = self.fees_collected
+            .checked_add(fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
 }
 
 // ========== RETURN TYPES ==========
@@ -222,21 +1955,6 @@ pub struct RouterStats {
     pub fee_rate_bps: u16,
     pub total_swaps: u64,
     pub total_volume: u64,
+    pub total_fees_collected: u64,
+    pub paused: bool,
 }
-
-// ========== ERRORS ==========
-
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Invalid Jupiter program provided")]
-    InvalidJupiterProgram,
-    
-    #[msg("Invalid fee rate - must be <= 1000 bps (10%)")]
-    InvalidFeeRate,
-    
-    #[msg("Empty instruction data")]
-    EmptyInstructionData,
-    
-    #[msg("Unauthorized")]
-    Unauthorized,
-}
\ No newline at end of file
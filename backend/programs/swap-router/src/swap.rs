@@ -0,0 +1,357 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::oracle::*;
+use crate::partner::Partner;
+use crate::user_stats::*;
+use crate::router::{MintStats, RouterState};
+use crate::tokens::*;
+use crate::utils::*;
+
+/// Accounts shared by every user swap that goes through the router.
+/// Balances are measured around the DEX CPI, then the router fee is
+/// skimmed from the output and stats are recorded.
+#[derive(Accounts)]
+pub struct SwapAccounts<'info> {
+    #[account(
+        mut,
+        seeds = [b"router_state"],
+        bump = router_state.bump
+    )]
+    pub router_state: Account<'info, RouterState>,
+
+    /// User executing the swap
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// User's input token account - spent amount is measured here
+    #[account(mut, token::authority = user)]
+    pub user_source_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// User's output token account - the fee is taken from here
+    #[account(mut, token::authority = user)]
+    pub user_destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Mint of user_destination_token, needed for transfer_checked
+    #[account(address = user_destination_token.mint @ ErrorCode::TokenMintMismatch)]
+    pub output_mint: InterfaceAccount<'info, Mint>,
+
+    /// Router treasury for the output mint
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", user_destination_token.mint.as_ref()],
+        bump,
+        token::mint = user_destination_token.mint
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", user_source_token.mint.as_ref()],
+        bump = input_mint_stats.bump
+    )]
+    pub input_mint_stats: Account<'info, MintStats>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", user_destination_token.mint.as_ref()],
+        bump = output_mint_stats.bump
+    )]
+    pub output_mint_stats: Account<'info, MintStats>,
+
+    /// Both token programs are passed; each transfer uses the program owning its accounts
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+
+    /// Referring partner; receives its revenue share of the router fee
+    #[account(mut, seeds = [b"partner", partner.authority.as_ref()], bump = partner.bump)]
+    pub partner: Option<Account<'info, Partner>>,
+
+    /// Partner token account for the output mint, owned by the partner PDA
+    #[account(mut)]
+    pub partner_fee_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// User's stats; when present the swap is recorded and volume tiers apply
+    #[account(mut, seeds = [b"user_stats", user.key().as_ref()], bump = user_stats.bump)]
+    pub user_stats: Option<Account<'info, UserSwapStats>>,
+
+    /// CHECK: Price feed PDAs for both mints, required while the oracle guard
+    /// is on; may be empty when a mint has no feed. When both mints have feeds
+    /// the execution price must stay close to the oracle price
+    #[account(seeds = [b"price_feed", user_source_token.mint.as_ref()], bump)]
+    pub input_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Oracle named by input_price_feed, validated when read
+    pub input_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: See input_price_feed
+    #[account(seeds = [b"price_feed", user_destination_token.mint.as_ref()], bump)]
+    pub output_price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Oracle named by output_price_feed, validated when read
+    pub output_oracle: Option<UncheckedAccount<'info>>,
+}
+
+/// Balances captured before the DEX CPI
+#[derive(Clone, Copy, Debug)]
+pub struct SwapSnapshot {
+    pub source_before: u64,
+    pub destination_before: u64,
+}
+
+/// Realized amounts of a settled swap
+#[derive(Clone, Copy, Debug)]
+pub struct SwapOutcome {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub net_amount: u64,
+}
+
+impl<'info> SwapAccounts<'info> {
+    pub fn snapshot(&self) -> SwapSnapshot {
+        SwapSnapshot {
+            source_before: self.user_source_token.amount,
+            destination_before: self.user_destination_token.amount,
+        }
+    }
+
+    /// Measure the swap from balance deltas, then finalize it.
+    /// Must be called after the DEX CPI.
+    pub fn settle(
+        &mut self,
+        snapshot: SwapSnapshot,
+        min_amount_out: Option<u64>,
+        max_amount_in: Option<u64>,
+        route_kind: RouteKind,
+    ) -> Result<SwapOutcome> {
+        // Deltas cannot be measured when input and output share a mint
+        require!(
+            self.user_source_token.mint != self.user_destination_token.mint,
+            ErrorCode::InvalidTokenPair
+        );
+
+        // Measure what the user actually spent and received
+        self.user_source_token.reload()?;
+        self.user_destination_token.reload()?;
+        let amount_in = snapshot.source_before
+            .checked_sub(self.user_source_token.amount)
+            .ok_or(ErrorCode::InvalidSwapAmount)?;
+        require!(amount_in > 0, ErrorCode::InvalidSwapAmount);
+        let amount_out = self.user_destination_token.amount
+            .checked_sub(snapshot.destination_before)
+            .ok_or(ErrorCode::InsufficientOutputAmount)?;
+        require!(amount_out > 0, ErrorCode::InsufficientOutputAmount);
+
+        self.finalize(amount_in, amount_out, min_amount_out, max_amount_in, route_kind)
+    }
+
+    /// Router fee rate for this swap; volume tiers apply to users that bring
+    /// their stats account
+    pub fn fee_rate_bps(&self, now: i64) -> u16 {
+        match &self.user_stats {
+            Some(user_stats) => effective_fee_rate(
+                self.router_state.fee_rate_bps,
+                &self.router_state.fee_tiers,
+                user_stats.rolling_volume(now),
+            ),
+            None => self.router_state.fee_rate_bps,
+        }
+    }
+
+    fn oracle_guard(&self) -> OracleGuardAccounts<'_, 'info> {
+        OracleGuardAccounts {
+            input_price_feed: self.input_price_feed.as_deref(),
+            input_oracle: self.input_oracle.as_deref(),
+            output_price_feed: self.output_price_feed.as_deref(),
+            output_oracle: self.output_oracle.as_deref(),
+        }
+    }
+
+    /// Enforce the caller's bounds, charge the router fee on the output
+    /// and record stats for already-measured amounts.
+    pub fn finalize(
+        &mut self,
+        amount_in: u64,
+        amount_out: u64,
+        min_amount_out: Option<u64>,
+        max_amount_in: Option<u64>,
+        route_kind: RouteKind,
+    ) -> Result<SwapOutcome> {
+        let clock = Clock::get()?;
+
+        // Round trips start and end in the same mint and cannot be priced
+        if !matches!(route_kind, RouteKind::Arbitrage { .. }) {
+            self.oracle_guard().check(&self.router_state, amount_in, amount_out, clock.unix_timestamp)?;
+        }
+
+        let fee_rate_bps = self.fee_rate_bps(clock.unix_timestamp);
+
+        // Skim the router fee from the output
+        let fee_amount = calculate_fee(amount_out, fee_rate_bps)?;
+        let net_amount = calculate_net_amount(amount_out, fee_rate_bps)?;
+
+        // Enforce caller bounds on the realized amounts
+        if let Some(max_amount_in) = max_amount_in {
+            require!(amount_in <= max_amount_in, ErrorCode::SlippageExceeded);
+        }
+        if let Some(min_amount_out) = min_amount_out {
+            require!(net_amount >= min_amount_out, ErrorCode::InsufficientOutputAmount);
+        }
+
+        // Split the fee with the referring partner, if any
+        let partner_fee = match (&self.partner, &self.partner_fee_account) {
+            (Some(partner), Some(partner_fee_account)) => {
+                require!(partner.active, ErrorCode::PartnerInactive);
+                require!(
+                    partner_fee_account.owner == partner.key()
+                        && partner_fee_account.mint == self.user_destination_token.mint,
+                    ErrorCode::InvalidPartnerFeeAccount
+                );
+                partner.share_of(fee_amount)?
+            }
+            (None, None) => 0,
+            _ => return err!(ErrorCode::InvalidPartnerFeeAccount),
+        };
+        let treasury_fee = fee_amount - partner_fee;
+
+        // Token-2022 transfer fees are withheld from what the recipients get;
+        // stats record the fees actually collected
+        let programs = [
+            &self.token_program.to_account_info(),
+            &self.token_2022_program.to_account_info(),
+        ];
+        let output_mint = self.output_mint.to_account_info();
+        let mut fees_collected = 0u64;
+
+        if treasury_fee > 0 {
+            transfer_tokens(
+                &self.user_destination_token.to_account_info(),
+                &output_mint,
+                &self.fee_treasury.to_account_info(),
+                &self.user.to_account_info(),
+                &programs,
+                treasury_fee,
+                &[],
+            )?;
+            fees_collected = amount_after_transfer_fee(&output_mint, treasury_fee)?;
+        }
+        if let (Some(partner), Some(partner_fee_account)) = (&mut self.partner, &self.partner_fee_account) {
+            let mut partner_received = 0;
+            if partner_fee > 0 {
+                transfer_tokens(
+                    &self.user_destination_token.to_account_info(),
+                    &output_mint,
+                    &partner_fee_account.to_account_info(),
+                    &self.user.to_account_info(),
+                    &programs,
+                    partner_fee,
+                    &[],
+                )?;
+                partner_received = amount_after_transfer_fee(&output_mint, partner_fee)?;
+            }
+            partner.record_accrual(partner_received)?;
+            fees_collected = fees_collected
+                .checked_add(partner_received)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            emit!(PartnerFeeAccrued {
+                partner: partner.key(),
+                mint: self.user_destination_token.mint,
+                amount: partner_received,
+            });
+        }
+
+        // Update router stats
+        let router = &mut self.router_state;
+        router.total_swaps = router.total_swaps.checked_add(1).unwrap();
+        router.total_volume = router.total_volume.saturating_add(amount_in);
+        router.total_fees_collected = router.total_fees_collected
+            .checked_add(fees_collected)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Update per-mint stats; each mint counts the swap once. For round
+        // trips both fields hold the same PDA; output_mint_stats is serialized
+        // last, so record everything there.
+        if self.input_mint_stats.key() == self.output_mint_stats.key() {
+            self.output_mint_stats.record_input(amount_in)?;
+        } else {
+            self.input_mint_stats.record_swap()?;
+            self.input_mint_stats.record_input(amount_in)?;
+        }
+        self.output_mint_stats.record_swap()?;
+        self.output_mint_stats.record_output(amount_out, fees_collected)?;
+        // Tier volume is the gross output, and only in the router's volume mints
+        if let Some(user_stats) = &mut self.user_stats {
+            let volume = if self.router_state.is_volume_mint(&self.user_destination_token.mint) {
+                amount_out
+            } else {
+                0
+            };
+            user_stats.record_swap(volume, clock.unix_timestamp)?;
+        }
+
+        emit!(FeeCollected {
+            user: self.user.key(),
+            mint: self.user_destination_token.mint,
+            amount_out,
+            fee_amount: fees_collected,
+        });
+
+        emit!(SwapExecuted {
+            user: self.user.key(),
+            input_mint: self.user_source_token.mint,
+            output_mint: self.user_destination_token.mint,
+            amount_in,
+            amount_out,
+            fee_amount,
+            route_kind,
+            slot: clock.slot,
+        });
+
+        Ok(SwapOutcome {
+            amount_in,
+            amount_out,
+            fee_amount,
+            net_amount,
+        })
+    }
+}
+
+/// Build a CPI instruction that forwards `accounts` to `program_id` unchanged
+pub fn passthrough_instruction(
+    program_id: Pubkey,
+    accounts: &[AccountInfo],
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts
+            .iter()
+            .map(|acc| AccountMeta {
+                pubkey: acc.key(),
+                is_signer: acc.is_signer,
+                is_writable: acc.is_writable,
+            })
+            .collect(),
+        data,
+    }
+}
+
+/// Deserialize an SPL Token or Token-2022 account that was not declared in the Accounts struct
+pub fn load_token_account(info: &AccountInfo) -> Result<TokenAccount> {
+    if *info.owner != anchor_spl::token::ID && *info.owner != anchor_spl::token_2022::ID {
+        return Err(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+    let data = info.try_borrow_data()?;
+    TokenAccount::try_deserialize(&mut &data[..])
+}
+
+/// Token balance of an account that was not declared in the Accounts struct
+pub fn token_balance(info: &AccountInfo) -> Result<u64> {
+    Ok(load_token_account(info)?.amount)
+}
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig}, BaseStateWithExtensions,
+        ExtensionType, StateWithExtensions,
+    },
+};
+use anchor_spl::token_2022_extensions::transfer_fee::{self, HarvestWithheldTokensToMint};
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::errors::ErrorCode;
+
+/// Mint extensions the router and vault cannot handle safely:
+/// transfers would need extra accounts (hooks), balances are not observable
+/// (confidential), tokens cannot move, or a third party can move them at will
+pub const UNSUPPORTED_MINT_EXTENSIONS: [ExtensionType; 6] = [
+    ExtensionType::TransferHook,
+    ExtensionType::ConfidentialTransferMint,
+    ExtensionType::ConfidentialTransferFeeConfig,
+    ExtensionType::ConfidentialMintBurn,
+    ExtensionType::NonTransferable,
+    ExtensionType::PermanentDelegate,
+];
+
+/// True if a Token-2022 mint carries any of UNSUPPORTED_MINT_EXTENSIONS.
+/// Legacy SPL Token mints have no extensions.
+pub fn has_unsupported_extensions(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state
+        .get_extension_types()?
+        .iter()
+        .any(|ext| UNSUPPORTED_MINT_EXTENSIONS.contains(ext)))
+}
+
+/// Reject mints the router cannot trade
+pub fn validate_mint(mint: &AccountInfo) -> Result<()> {
+    require!(!has_unsupported_extensions(mint)?, ErrorCode::UnsupportedMintExtension);
+    Ok(())
+}
+
+/// Amount withheld by the Token-2022 transfer-fee extension when moving
+/// `amount` of `mint` in the current epoch (0 for mints without it)
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(ErrorCode::MathOverflow.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// What the recipient ends up with after the transfer fee
+pub fn amount_after_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    amount
+        .checked_sub(transfer_fee(mint, amount)?)
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+/// Move the transfer fees withheld in a Token-2022 token account to its
+/// mint, which `close_account` requires. Harvesting is permissionless and a
+/// no-op for accounts without withheld fees.
+pub fn harvest_withheld_fees<'info>(
+    account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if *account.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let withheld = {
+        let data = account.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        state
+            .get_extension::<TransferFeeAmount>()
+            .map(|fee| u64::from(fee.withheld_amount))
+            .unwrap_or(0)
+    };
+    if withheld == 0 {
+        return Ok(());
+    }
+    transfer_fee::harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.clone(),
+            HarvestWithheldTokensToMint {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+            },
+        ),
+        vec![account.clone()],
+    )
+}
+
+/// Decimals of a mint that was not declared in the Accounts struct
+pub fn mint_decimals(mint: &AccountInfo) -> Result<u8> {
+    require!(
+        *mint.owner == anchor_spl::token::ID || *mint.owner == spl_token_2022::ID,
+        ErrorCode::InvalidTokenProgram
+    );
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(state.base.decimals)
+}
+
+/// Pick the token program owning `account` among the programs passed in
+pub fn token_program_for<'info>(
+    account: &AccountInfo<'info>,
+    programs: &[&AccountInfo<'info>],
+) -> Result<AccountInfo<'info>> {
+    programs
+        .iter()
+        .find(|program| program.key == account.owner)
+        .map(|program| (*program).clone())
+        .ok_or(ErrorCode::InvalidTokenProgram.into())
+}
+
+/// transfer_checked through whichever token program owns `from`.
+/// Pass empty `signer_seeds` when `authority` signs the transaction.
+pub fn transfer_tokens<'info>(
+    from: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    programs: &[&AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let token_program = token_program_for(from, programs)?;
+    let decimals = mint_decimals(mint)?;
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program,
+            TransferChecked {
+                from: from.clone(),
+                mint: mint.clone(),
+                to: to.clone(),
+                authority: authority.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        decimals,
+    )
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+// Maximum number of volume tiers in the router fee table
+pub const MAX_FEE_TIERS: usize = 4;
+
+// Maximum number of quote mints whose volume counts toward fee tiers
+pub const MAX_VOLUME_MINTS: usize = 4;
+
+// Length of the rolling volume window, in days
+pub const VOLUME_WINDOW_DAYS: usize = 30;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Discounted fee for users whose rolling volume reaches `min_volume`
+/// A tier with min_volume == 0 is an empty slot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeTier {
+    pub min_volume: u64,        // 8 (rolling 30-day volume, raw units of the volume mints)
+    pub fee_rate_bps: u16,      // 2
+}
+
+impl FeeTier {
+    pub const INIT_SPACE: usize = 8 + 2;
+}
+
+/// Fee rate for a user with `rolling_volume`: the best tier reached, never
+/// above the base rate
+pub fn effective_fee_rate(base_fee_rate_bps: u16, tiers: &[FeeTier], rolling_volume: u64) -> u16 {
+    tiers
+        .iter()
+        .filter(|t| t.min_volume > 0 && rolling_volume >= t.min_volume)
+        .map(|t| t.fee_rate_bps)
+        .fold(base_fee_rate_bps, u16::min)
+}
+
+/// Check an authority-supplied tier table and pad it to MAX_FEE_TIERS
+pub fn build_fee_tiers(tiers: &[FeeTier]) -> Result<[FeeTier; MAX_FEE_TIERS]> {
+    require!(tiers.len() <= MAX_FEE_TIERS, ErrorCode::InvalidFeeTiers);
+
+    let mut table = [FeeTier::default(); MAX_FEE_TIERS];
+    for (i, tier) in tiers.iter().enumerate() {
+        require!(tier.min_volume > 0, ErrorCode::InvalidFeeTiers);
+        require!(tier.fee_rate_bps <= 1000, ErrorCode::InvalidFeeRate);
+        if i > 0 {
+            require!(tier.min_volume > tiers[i - 1].min_volume, ErrorCode::InvalidFeeTiers);
+        }
+        table[i] = *tier;
+    }
+    Ok(table)
+}
+
+/// Per-user swap statistics (seeds: ["user_stats", user])
+/// Volume is bucketed by day so the 30-day window can roll forward.
+/// Only output paid in one of the router's volume mints is counted, so tiers
+/// cannot be reached by trading a mint anyone can create.
+#[account]
+pub struct UserSwapStats {
+    pub user: Pubkey,                               // 32
+    pub swap_count: u64,                            // 8
+    pub total_volume: u128,                         // 16 (raw units of the volume mints)
+    pub last_day: i64,                              // 8  (unix day of the newest bucket)
+    pub daily_volume: [u64; VOLUME_WINDOW_DAYS],    // 8 * 30
+    pub bump: u8,                                   // 1
+}
+
+impl UserSwapStats {
+    pub const INIT_SPACE: usize = 32 + 8 + 16 + 8 + 8 * VOLUME_WINDOW_DAYS + 1;
+
+    /// Volume over the last 30 days as of `now` (unix timestamp)
+    pub fn rolling_volume(&self, now: i64) -> u64 {
+        let today = now / SECONDS_PER_DAY;
+        let age = today.saturating_sub(self.last_day);
+        if age >= VOLUME_WINDOW_DAYS as i64 {
+            return 0;
+        }
+        // Buckets older than the window are the `age` oldest ones
+        (0..VOLUME_WINDOW_DAYS as i64 - age)
+            .map(|d| self.daily_volume[Self::bucket(self.last_day - d)])
+            .fold(0u64, u64::saturating_add)
+    }
+
+    /// Count a swap; `volume` is 0 when the output is not a volume mint
+    pub fn record_swap(&mut self, volume: u64, now: i64) -> Result<()> {
+        let today = now / SECONDS_PER_DAY;
+
+        // Clear buckets for the days skipped since the last swap
+        if today > self.last_day {
+            let elapsed = (today - self.last_day).min(VOLUME_WINDOW_DAYS as i64);
+            for d in 0..elapsed {
+                self.daily_volume[Self::bucket(today - d)] = 0;
+            }
+            self.last_day = today;
+        }
+
+        let bucket = &mut self.daily_volume[Self::bucket(today)];
+        *bucket = bucket.saturating_add(volume);
+        self.swap_count = self.swap_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.total_volume = self.total_volume
+            .checked_add(volume as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    fn bucket(day: i64) -> usize {
+        day.rem_euclid(VOLUME_WINDOW_DAYS as i64) as usize
+    }
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[derive(Accounts)]
+pub struct InitializeUserStats<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserSwapStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserSwapStats>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
 use crate::errors::ErrorCode;
 
+/// True once the clock is past an optional slot or unix-time expiry
+pub fn is_expired(clock: &Clock, valid_until_slot: Option<u64>, deadline_unix_ts: Option<i64>) -> bool {
+    valid_until_slot.is_some_and(|slot| clock.slot > slot)
+        || deadline_unix_ts.is_some_and(|deadline| clock.unix_timestamp > deadline)
+}
+
 /// Calculate fee amount based on input amount and fee rate
 pub fn calculate_fee(amount: u64, fee_rate: u16) -> Result<u64> {
     amount
@@ -17,6 +23,15 @@ pub fn calculate_net_amount(amount: u64, fee_rate: u16) -> Result<u64> {
         .ok_or(ErrorCode::MathOverflow.into())
 }
 
+/// Gross amount that nets exactly `net_amount` once the fee is deducted
+pub fn calculate_gross_amount(net_amount: u64, fee_rate: u16) -> Result<u64> {
+    let gross = (net_amount as u128)
+        .checked_mul(10000)
+        .and_then(|result| result.checked_div(10000 - fee_rate as u128))
+        .ok_or(ErrorCode::MathOverflow)?;
+    u64::try_from(gross).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
 /// Validate slippage tolerance
 pub fn validate_slippage(
     expected_amount: u64,
@@ -37,12 +52,7 @@ pub fn validate_slippage(
 }
 
 /// Calculate price impact
-pub fn calculate_price_impact(
-    amount_in: u64,
-    amount_out: u64,
-    pool_balance_in: u64,
-    pool_balance_out: u64,
-) -> Result<u16> {
+pub fn calculate_price_impact(amount_in: u64, pool_balance_in: u64) -> Result<u16> {
     // Simplified price impact calculation
     // Price impact = (amount_in / pool_balance_in) * 10000 (in basis points)
     
@@ -66,4 +76,95 @@ pub fn validate_dex_program(program_id: &Pubkey, expected_program: &Pubkey) -> R
         ErrorCode::InvalidDexProgram
     );
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Integer square root (floor)
+pub fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    
+    // Newton's method
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// Constant-product output after an LP fee of `fee_bps` on the input:
+/// reserve_out * in_after_fee / (reserve_in + in_after_fee).
+/// The whole input still enters the pool, so the fee stays in the reserves.
+pub fn calculate_swap_output(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bps: u16,
+) -> Result<u64> {
+    require!(reserve_in > 0 && reserve_out > 0, ErrorCode::InsufficientLiquidity);
+    
+    let amount_in_with_fee = 10000u16
+        .checked_sub(fee_bps)
+        .and_then(|rate| (amount_in as u128).checked_mul(rate as u128))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let numerator = (reserve_out as u128)
+        .checked_mul(amount_in_with_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let denominator = (reserve_in as u128)
+        .checked_mul(10000)
+        .and_then(|reserve| reserve.checked_add(amount_in_with_fee))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let amount_out = numerator
+        .checked_div(denominator)
+        .ok_or(ErrorCode::MathOverflow)?;
+    
+    require!(amount_out > 0, ErrorCode::InsufficientOutputAmount);
+    u64::try_from(amount_out).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// Split a deposit into the pool ratio.
+/// Returns (amount_a, amount_b, lp_tokens); the deposited side that is not
+/// fully used is rounded up in favor of the pool.
+pub fn calculate_deposit(
+    max_amount_a: u64,
+    max_amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+) -> Result<(u64, u64, u64)> {
+    require!(reserve_a > 0 && reserve_b > 0, ErrorCode::InsufficientLiquidity);
+    
+    let ceil_ratio = |amount: u64, numerator: u64, denominator: u64| -> Result<u64> {
+        let value = (amount as u128)
+            .checked_mul(numerator as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .div_ceil(denominator as u128);
+        u64::try_from(value).map_err(|_| ErrorCode::MathOverflow.into())
+    };
+    
+    let b_for_all_a = ceil_ratio(max_amount_a, reserve_b, reserve_a)?;
+    let (amount_a, amount_b) = if b_for_all_a <= max_amount_b {
+        (max_amount_a, b_for_all_a)
+    } else {
+        (ceil_ratio(max_amount_b, reserve_a, reserve_b)?, max_amount_b)
+    };
+    
+    let lp_from_a = (amount_a as u128) * (lp_supply as u128) / (reserve_a as u128);
+    let lp_from_b = (amount_b as u128) * (lp_supply as u128) / (reserve_b as u128);
+    let lp_tokens = u64::try_from(std::cmp::min(lp_from_a, lp_from_b))
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    
+    require!(lp_tokens > 0, ErrorCode::InvalidSwapAmount);
+    Ok((amount_a, amount_b, lp_tokens))
+}
+
+/// Share of a reserve owed for burning lp_amount (rounded down)
+pub fn calculate_withdrawal(lp_amount: u64, reserve: u64, lp_supply: u64) -> Result<u64> {
+    let amount = (lp_amount as u128)
+        .checked_mul(reserve as u128)
+        .and_then(|v| v.checked_div(lp_supply as u128))
+        .ok_or(ErrorCode::MathOverflow)?;
+    u64::try_from(amount).map_err(|_| ErrorCode::MathOverflow.into())
+}
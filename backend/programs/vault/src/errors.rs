@@ -14,10 +14,40 @@ pub enum ErrorCode {
     InvalidAuthority,
     #[msg("Slippage tolerance exceeded")]
     SlippageExceeded,
-    #[msg("Invalid token mint - only SOL/WSOL supported")]
+    #[msg("Invalid token mint for this vault")]
     InvalidTokenMint,
     #[msg("Insufficient vault balance")]
     InsufficientVaultBalance,
     #[msg("Invalid minimum profit requirement")]
     InvalidMinProfit,
+    #[msg("Mint uses an unsupported Token-2022 extension")]
+    UnsupportedMintExtension,
+    #[msg("Arbitrage expired: past valid_until_slot or deadline")]
+    ArbitrageExpired,
+    #[msg("A flash loan is outstanding")]
+    FlashLoanActive,
+    #[msg("Flash loan must be repaid by a matching flash_repay in the same transaction")]
+    FlashLoanNotRepaid,
+    #[msg("Flash loan instructions must be called directly")]
+    FlashLoanCpiNotAllowed,
+    #[msg("Flash loan fee above the limit")]
+    InvalidFlashFee,
+    #[msg("Token account is not the vault's canonical token account")]
+    InvalidVaultTokenAccount,
+    #[msg("Share mint is not this vault's share mint")]
+    InvalidShareMint,
+    #[msg("Share token name, symbol or uri too long or empty")]
+    InvalidShareMetadata,
+    #[msg("Deposit too small to mint any shares")]
+    ZeroShares,
+    #[msg("Fee configuration above the limits")]
+    InvalidFeeConfig,
+    #[msg("Token account is not owned by the protocol fee recipient")]
+    InvalidFeeRecipient,
+    #[msg("Account is not the original vault")]
+    InvalidLegacyVault,
+    #[msg("Protocol fee requires a fee recipient")]
+    MissingFeeRecipient,
+    #[msg("Protocol fee token account is required")]
+    MissingProtocolFeeToken,
 }
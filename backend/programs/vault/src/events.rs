@@ -1,15 +1,32 @@
 use anchor_lang::prelude::*;
 
+#[event]
+pub struct VaultConfigUpdated {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct VaultInitialized {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub share_mint: Pubkey,
+    pub authority: Pubkey,
+}
+
 #[event]
 pub struct ArbitrageExecuted {
+    pub vault: Pubkey,
     pub executor: Pubkey,
     pub profit: u64,
     pub executor_fee: u64,
+    pub protocol_fee: u64,
     pub vault_profit: u64,
 }
 
 #[event]
 pub struct Deposited {
+    pub vault: Pubkey,
     pub user: Pubkey,
     pub amount: u64,
     pub shares: u64,
@@ -17,7 +34,39 @@ pub struct Deposited {
 
 #[event]
 pub struct Withdrawn {
+    pub vault: Pubkey,
     pub user: Pubkey,
     pub amount: u64,
     pub shares: u64,
 }
+
+#[event]
+pub struct FlashLoanBorrowed {
+    pub vault: Pubkey,
+    pub borrower: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct FlashLoanRepaid {
+    pub vault: Pubkey,
+    pub borrower: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct FlashFeeUpdated {
+    pub vault: Pubkey,
+    pub old_flash_fee_bps: u16,
+    pub new_flash_fee_bps: u16,
+}
+
+#[event]
+pub struct FeesUpdated {
+    pub vault: Pubkey,
+    pub executor_fee_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub protocol_fee_recipient: Pubkey,
+}
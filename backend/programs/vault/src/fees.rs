@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+// Defaults for a new vault: the historical 10% executor incentive
+pub const DEFAULT_EXECUTOR_FEE_BPS: u16 = 1_000;
+pub const DEFAULT_PROTOCOL_FEE_BPS: u16 = 0;
+
+// Upper bounds, so depositors always keep at least half of the profit
+pub const MAX_EXECUTOR_FEE_BPS: u16 = 3_000;
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 3_000;
+pub const MAX_TOTAL_FEE_BPS: u16 = 5_000;
+
+pub fn validate_fees(executor_fee_bps: u16, protocol_fee_bps: u16) -> Result<()> {
+    require!(
+        executor_fee_bps <= MAX_EXECUTOR_FEE_BPS
+            && protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS
+            && executor_fee_bps + protocol_fee_bps <= MAX_TOTAL_FEE_BPS,
+        ErrorCode::InvalidFeeConfig
+    );
+    Ok(())
+}
+
+/// Executor and protocol cuts of `profit`, each rounded down so the
+/// remainder stays with depositors
+pub fn split_profit(profit: u64, executor_fee_bps: u16, protocol_fee_bps: u16) -> Result<(u64, u64)> {
+    Ok((bps_of(profit, executor_fee_bps)?, bps_of(profit, protocol_fee_bps)?))
+}
+
+fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / 10_000;
+    u64::try_from(value).map_err(|_| error!(ErrorCode::MathOverflow))
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+use crate::errors::ErrorCode;
+use crate::instruction::FlashRepay as FlashRepayIx;
+
+// Upper bound for the flash loan fee
+pub const MAX_FLASH_FEE_BPS: u16 = 1_000;
+
+/// Fee owed on a flash loan of `amount`, rounded up so small loans still pay
+pub fn flash_fee(amount: u64, flash_fee_bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(flash_fee_bps as u128)
+        .and_then(|v| v.checked_add(9_999))
+        .ok_or(ErrorCode::MathOverflow)?
+        / 10_000;
+    u64::try_from(fee).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+/// Flash loan instructions must be top-level, so introspection sees the
+/// real transaction and no program can wrap a borrow around its own logic
+pub fn require_top_level() -> Result<()> {
+    require!(
+        get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT,
+        ErrorCode::FlashLoanCpiNotAllowed
+    );
+    Ok(())
+}
+
+/// Scan the instructions after the current one for the first `flash_repay`
+/// of this program and require it to repay `amount` into the same vault
+/// token account
+pub fn require_matching_repay(
+    instructions: &AccountInfo,
+    vault: &Pubkey,
+    vault_token: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let mut index = load_current_index_checked(instructions)? as usize + 1;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+        if ix.program_id == crate::ID && ix.data.starts_with(FlashRepayIx::DISCRIMINATOR) {
+            let repaid = ix.data
+                .get(8..16)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u64::from_le_bytes);
+            require!(
+                repaid == Some(amount)
+                    && ix.accounts.len() >= 2
+                    && ix.accounts[0].pubkey == *vault
+                    && ix.accounts[1].pubkey == *vault_token,
+                ErrorCode::FlashLoanNotRepaid
+            );
+            return Ok(());
+        }
+        index += 1;
+    }
+    err!(ErrorCode::FlashLoanNotRepaid)
+}
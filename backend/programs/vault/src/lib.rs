@@ -1,9 +1,16 @@
+#[allow(clippy::module_inception)]
 pub mod vault;
 pub mod state;
 pub mod events;
 pub mod errors;
+pub mod flash_loan;
+pub mod shares;
+pub mod fees;
 
 pub use vault::*;
 pub use state::*;
 pub use events::*;
-pub use errors::*;
\ No newline at end of file
+pub use errors::*;
+pub use flash_loan::*;
+pub use shares::*;
+pub use fees::*;
\ No newline at end of file
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+// Metaplex limits, so share tokens display like any other token
+pub const MAX_SHARE_NAME_LEN: usize = 32;
+pub const MAX_SHARE_SYMBOL_LEN: usize = 10;
+pub const MAX_SHARE_URI_LEN: usize = 200;
+
+// The vault prices shares as if it always held this many extra shares and
+// tokens. A donation to a nearly empty vault then mostly accrues to the
+// virtual position, so inflating the share price to round the next
+// depositor down to nothing costs the attacker ~1000x what the victim loses
+pub const VIRTUAL_SHARES: u64 = 1_000;
+pub const VIRTUAL_ASSETS: u64 = 1_000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+/// Shares worth `assets` given the outstanding shares and the vault balance;
+/// an empty vault mints one share per token
+pub fn shares_for_assets(
+    assets: u64,
+    total_shares: u64,
+    total_assets: u64,
+    rounding: Rounding,
+) -> Result<u64> {
+    mul_div(
+        assets,
+        total_shares as u128 + VIRTUAL_SHARES as u128,
+        total_assets as u128 + VIRTUAL_ASSETS as u128,
+        rounding,
+    )
+}
+
+/// Tokens `shares` are worth given the outstanding shares and the vault balance
+pub fn assets_for_shares(
+    shares: u64,
+    total_shares: u64,
+    total_assets: u64,
+    rounding: Rounding,
+) -> Result<u64> {
+    mul_div(
+        shares,
+        total_assets as u128 + VIRTUAL_ASSETS as u128,
+        total_shares as u128 + VIRTUAL_SHARES as u128,
+        rounding,
+    )
+}
+
+fn mul_div(value: u64, numerator: u128, denominator: u128, rounding: Rounding) -> Result<u64> {
+    let product = (value as u128)
+        .checked_mul(numerator)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let result = match rounding {
+        Rounding::Down => product / denominator,
+        Rounding::Up => product.div_ceil(denominator),
+    };
+    u64::try_from(result).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+pub fn validate_share_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    require!(
+        !name.is_empty()
+            && name.len() <= MAX_SHARE_NAME_LEN
+            && !symbol.is_empty()
+            && symbol.len() <= MAX_SHARE_SYMBOL_LEN
+            && uri.len() <= MAX_SHARE_URI_LEN,
+        ErrorCode::InvalidShareMetadata
+    );
+    Ok(())
+}
@@ -3,11 +3,29 @@ use anchor_lang::prelude::*;
 #[account]
 pub struct Vault {
     pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub share_mint: Pubkey,
     pub swap_router: Pubkey,
     pub total_shares: u64,
     pub bump: u8,
+    pub flash_fee_bps: u16,
+    pub flash_loan_amount: u64,
+    pub flash_loan_fee: u64,
+    pub executor_fee_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub protocol_fee_recipient: Pubkey,
 }
 
+/// Who may create vaults; set by the program upgrade authority
+#[account]
+pub struct VaultConfig {
+    pub admin: Pubkey,
+    pub bump: u8,
+}
+
+/// Share balance in the original single `["vault"]` vault, from before vaults
+/// were keyed by mint and shares were a token; only read by `legacy_withdraw`
 #[account]
 pub struct UserPosition {
     pub owner: Pubkey,
@@ -15,7 +33,15 @@ pub struct UserPosition {
 }
 
 impl Vault {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 32 + 8 + 1 + 2 + 8 + 8 + 2 + 2 + 32;
+    // authority, swap_router, total_shares and bump: the prefix every
+    // version of the original `["vault"]` account starts with
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 8 + 1;
+    pub const LEGACY_TOTAL_SHARES_OFFSET: usize = 8 + 32 + 32;
+}
+
+impl VaultConfig {
+    pub const LEN: usize = 8 + 32 + 1;
 }
 
 impl UserPosition {
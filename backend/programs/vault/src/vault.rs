@@ -1,63 +1,206 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata;
+use anchor_spl::token_2022_extensions::{token_metadata_initialize, TokenMetadataInitialize};
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
 use crate::state::*;
 use crate::events::*;
 use crate::errors::ErrorCode;
+use crate::flash_loan::*;
+use crate::shares::*;
+use crate::fees::*;
 declare_id!("6Y9Zhzdpfjt7qL59WA1Q8WMVRVoXhdpcTKKP1Uw4FLXz");
 
-// Wrapped SOL mint address
+// The original `["vault"]` vault only ever held wrapped SOL
 pub const WSOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
 
 #[program]
 pub mod vault {
     use super::*;
 
-    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+    /// Create the config naming the admin allowed to create vaults;
+    /// only the program upgrade authority can call it, once
+    pub fn initialize_config(ctx: Context<InitializeConfig>, admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = admin;
+        config.bump = ctx.bumps.config;
+
+        emit!(VaultConfigUpdated {
+            old_admin: Pubkey::default(),
+            new_admin: admin,
+        });
+
+        Ok(())
+    }
+
+    /// Hand vault creation over to `new_admin` (config admin only)
+    pub fn set_config_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_admin = config.admin;
+        config.admin = new_admin;
+
+        emit!(VaultConfigUpdated {
+            old_admin,
+            new_admin,
+        });
+
+        Ok(())
+    }
+
+    /// Create the vault for `mint`; each mint has its own vault, shares and profit.
+    /// Shares are a Token-2022 mint carrying `name`, `symbol` and `uri` as
+    /// on-mint metadata, updatable by the vault authority. Only the config
+    /// admin can create vaults, and it becomes their authority
+    pub fn initialize_vault(
+        ctx: Context<InitializeVault>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require!(
+            !swap_router::has_unsupported_extensions(&ctx.accounts.mint.to_account_info())?,
+            ErrorCode::UnsupportedMintExtension
+        );
+        validate_share_metadata(&name, &symbol, &uri)?;
+
+        // Token-2022 reallocs the mint for the metadata but the rent must
+        // already be there
+        let share_mint_info = ctx.accounts.share_mint.to_account_info();
+        let metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(ctx.accounts.authority.key()))?,
+            mint: share_mint_info.key(),
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            additional_metadata: vec![],
+        };
+        let metadata_len = metadata.tlv_size_of()?;
+        let rent = Rent::get()?.minimum_balance(share_mint_info.data_len() + metadata_len);
+        let top_up = rent.saturating_sub(share_mint_info.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: share_mint_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[b"vault".as_ref(), mint_key.as_ref(), &[ctx.bumps.vault]];
+        let signer_seeds = &[&seeds[..]];
+
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                ctx.accounts.share_token_program.to_account_info(),
+                TokenMetadataInitialize {
+                    program_id: ctx.accounts.share_token_program.to_account_info(),
+                    metadata: share_mint_info.clone(),
+                    update_authority: ctx.accounts.authority.to_account_info(),
+                    mint_authority: ctx.accounts.vault.to_account_info(),
+                    mint: share_mint_info,
+                },
+                signer_seeds,
+            ),
+            name,
+            symbol,
+            uri,
+        )?;
+
         let vault = &mut ctx.accounts.vault;
         vault.authority = ctx.accounts.authority.key();
+        vault.mint = ctx.accounts.mint.key();
+        vault.token_account = ctx.accounts.vault_token.key();
+        vault.share_mint = ctx.accounts.share_mint.key();
         vault.swap_router = ctx.accounts.swap_router.key();
         vault.total_shares = 0;
         vault.bump = ctx.bumps.vault;
+        vault.flash_fee_bps = 0;
+        vault.flash_loan_amount = 0;
+        vault.flash_loan_fee = 0;
+        vault.executor_fee_bps = DEFAULT_EXECUTOR_FEE_BPS;
+        vault.protocol_fee_bps = DEFAULT_PROTOCOL_FEE_BPS;
+        vault.protocol_fee_recipient = vault.authority;
+
+        emit!(VaultInitialized {
+            vault: vault.key(),
+            mint: vault.mint,
+            share_mint: vault.share_mint,
+            authority: vault.authority,
+        });
         
         Ok(())
     }
 
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        let vault = &mut ctx.accounts.vault;
-        let user_position = &mut ctx.accounts.user_position;
+    /// Deposit `amount` of the base asset; fails unless at least
+    /// `min_shares_out` shares are minted
+    pub fn deposit(ctx: Context<Deposit>, amount: u64, min_shares_out: u64) -> Result<()> {
+        // Share price is meaningless while lent-out tokens are missing
+        require!(ctx.accounts.vault.flash_loan_amount == 0, ErrorCode::FlashLoanActive);
+
+        // Transfer hooks and confidential balances would break share accounting
+        require!(
+            !swap_router::has_unsupported_extensions(&ctx.accounts.mint.to_account_info())?,
+            ErrorCode::UnsupportedMintExtension
+        );
+
+        let vault_balance = ctx.accounts.vault_token.amount;
 
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.user_token.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.vault_token.to_account_info(),
                     authority: ctx.accounts.user.to_account_info(),
                 },
             ),
             amount,
+            ctx.accounts.mint.decimals,
         )?;
 
-        let shares = if vault.total_shares == 0 {
-            amount
-        } else {
-            let vault_balance = ctx.accounts.vault_token.amount;
-            amount.checked_mul(vault.total_shares)
-                .ok_or(ErrorCode::MathOverflow)?
-                .checked_div(vault_balance)
-                .ok_or(ErrorCode::MathOverflow)?
-        };
-
-        user_position.shares = user_position.shares.checked_add(shares)
+        // Shares are issued for what the vault received, net of any transfer fee
+        ctx.accounts.vault_token.reload()?;
+        let received = ctx.accounts.vault_token.amount
+            .checked_sub(vault_balance)
             .ok_or(ErrorCode::MathOverflow)?;
-        user_position.owner = ctx.accounts.user.key();
+
+        let vault = &mut ctx.accounts.vault;
+        let shares = shares_for_assets(received, vault.total_shares, vault_balance, Rounding::Down)?;
+        require!(shares > 0, ErrorCode::ZeroShares);
+        require!(shares >= min_shares_out, ErrorCode::SlippageExceeded);
+
+        let seeds = &[b"vault".as_ref(), vault.mint.as_ref(), &[vault.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.share_token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    to: ctx.accounts.user_share_token.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            shares,
+        )?;
+
         vault.total_shares = vault.total_shares.checked_add(shares)
             .ok_or(ErrorCode::MathOverflow)?;
 
         emit!(Deposited {
+            vault: vault.key(),
             user: ctx.accounts.user.key(),
-            amount,
+            amount: received,
             shares,
         });
 
@@ -68,19 +211,18 @@ pub mod vault {
         ctx: Context<'_, '_, '_, 'info, ExecuteArbitrage<'info>>,
         jupiter_instruction_data: Vec<u8>,
         min_profit: u64,
+        valid_until_slot: Option<u64>,
+        deadline_unix_ts: Option<i64>,
     ) -> Result<()> {
         let vault = &ctx.accounts.vault;
+        require!(vault.flash_loan_amount == 0, ErrorCode::FlashLoanActive);
 
-        // Validate that this is a SOL-based arbitrage
+        // Stale opportunities landing late are a loss, not a no-op
         require!(
-            ctx.accounts.vault_token.mint == WSOL_MINT,
-            ErrorCode::InvalidTokenMint
+            !swap_router::is_expired(&Clock::get()?, valid_until_slot, deadline_unix_ts),
+            ErrorCode::ArbitrageExpired
         );
-        require!(
-            ctx.accounts.executor_token.mint == WSOL_MINT,
-            ErrorCode::InvalidTokenMint
-        );
-        
+
         // Ensure vault has sufficient balance for arbitrage
         let initial_balance = ctx.accounts.vault_token.amount;
         require!(initial_balance > 0, ErrorCode::InsufficientVaultBalance);
@@ -89,7 +231,7 @@ pub mod vault {
         require!(min_profit > 0, ErrorCode::InvalidMinProfit);
 
         let vault_bump = vault.bump;
-        let vault_seeds_data = vec![b"vault".to_vec(), vec![vault_bump]];
+        let vault_seeds_data = vec![b"vault".to_vec(), vault.mint.to_bytes().to_vec(), vec![vault_bump]];
         let seeds_slice: Vec<&[u8]> = vault_seeds_data.iter().map(|s| s.as_slice()).collect();
         let signer_seeds = &[seeds_slice.as_slice()];
 
@@ -97,6 +239,8 @@ pub mod vault {
         let cpi_accounts = swap_router::cpi::accounts::ExecuteVaultJupiterSwap {
             router_state: ctx.accounts.router_state.to_account_info(),
             vault_authority: vault.to_account_info(),
+            vault_source_token: ctx.accounts.vault_token.to_account_info(),
+            vault_destination_token: ctx.accounts.vault_token.to_account_info(),
             jupiter_program: ctx.accounts.jupiter_program.to_account_info(),
         };
 
@@ -115,7 +259,8 @@ pub mod vault {
         ctx.accounts.vault_token.reload()?;
         let final_balance = ctx.accounts.vault_token.amount;
 
-        // Calculate actual profit (must be positive)
+        // Calculate actual profit (must be positive); balances are measured,
+        // so any Token-2022 transfer fee on the swap legs is already deducted
         require!(final_balance > initial_balance, ErrorCode::InsufficientProfit);
         let profit = final_balance.checked_sub(initial_balance)
             .ok_or(ErrorCode::InsufficientProfit)?;
@@ -125,80 +270,322 @@ pub mod vault {
         
         msg!("Arbitrage executed: Initial={}, Final={}, Profit={}", initial_balance, final_balance, profit);
 
-        let executor_fee = profit.checked_mul(10)
-            .and_then(|v| v.checked_div(100))
-            .ok_or(ErrorCode::MathOverflow)?;
+        let (executor_fee, protocol_fee) =
+            split_profit(profit, vault.executor_fee_bps, vault.protocol_fee_bps)?;
 
         let seeds_ref: Vec<&[u8]> = vault_seeds_data.iter().map(|s| s.as_slice()).collect();
         let signer = &[seeds_ref.as_slice()];
 
-        token::transfer(
+        if executor_fee > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.vault_token.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: ctx.accounts.executor_token.to_account_info(),
+                        authority: vault.to_account_info(),
+                    },
+                    signer,
+                ),
+                executor_fee,
+                ctx.accounts.mint.decimals,
+            )?;
+        }
+
+        if protocol_fee > 0 {
+            let protocol_fee_token = ctx.accounts.protocol_fee_token.as_ref()
+                .ok_or(ErrorCode::MissingProtocolFeeToken)?;
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.vault_token.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: protocol_fee_token.to_account_info(),
+                        authority: vault.to_account_info(),
+                    },
+                    signer,
+                ),
+                protocol_fee,
+                ctx.accounts.mint.decimals,
+            )?;
+        }
+
+        emit!(ArbitrageExecuted {
+            vault: vault.key(),
+            executor: ctx.accounts.executor.key(),
+            profit,
+            executor_fee,
+            protocol_fee,
+            vault_profit: profit - executor_fee - protocol_fee,
+        });
+
+        Ok(())
+    }
+
+    /// Burn `shares` from the caller's share token account and pay out
+    /// their part of the vault balance, rounded down; fails if that is less
+    /// than `min_assets_out`
+    pub fn withdraw(ctx: Context<Withdraw>, shares: u64, min_assets_out: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(vault.flash_loan_amount == 0, ErrorCode::FlashLoanActive);
+
+        let amount = assets_for_shares(
+            shares,
+            vault.total_shares,
+            ctx.accounts.vault_token.amount,
+            Rounding::Down,
+        )?;
+        require!(amount >= min_assets_out, ErrorCode::SlippageExceeded);
+
+        ctx.accounts.redeem(shares, amount)
+    }
+
+    /// Withdraw exactly `assets`, burning the shares they are worth rounded
+    /// up; fails if that is more than `max_shares_in`
+    pub fn withdraw_assets(ctx: Context<Withdraw>, assets: u64, max_shares_in: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(vault.flash_loan_amount == 0, ErrorCode::FlashLoanActive);
+
+        let shares = shares_for_assets(
+            assets,
+            vault.total_shares,
+            ctx.accounts.vault_token.amount,
+            Rounding::Up,
+        )?;
+        require!(shares <= max_shares_in, ErrorCode::SlippageExceeded);
+
+        ctx.accounts.redeem(shares, assets)
+    }
+
+    /// Lend `amount` of the vault's tokens for the rest of the transaction.
+    /// A later top-level `flash_repay(amount)` on the same vault token account
+    /// must be present; it returns the principal plus the fee, which stays in
+    /// the vault for share holders.
+    pub fn flash_borrow(ctx: Context<FlashBorrow>, amount: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(vault.flash_loan_amount == 0, ErrorCode::FlashLoanActive);
+        require!(
+            amount > 0 && amount <= ctx.accounts.vault_token.amount,
+            ErrorCode::InsufficientVaultBalance
+        );
+        require_top_level()?;
+        require_matching_repay(
+            &ctx.accounts.instructions,
+            &vault.key(),
+            &ctx.accounts.vault_token.key(),
+            amount,
+        )?;
+
+        let fee = flash_fee(amount, vault.flash_fee_bps)?;
+        let seeds = &[b"vault".as_ref(), vault.mint.as_ref(), &[vault.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.vault_token.to_account_info(),
-                    to: ctx.accounts.executor_token.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.borrower_token.to_account_info(),
                     authority: vault.to_account_info(),
                 },
-                signer,
+                signer_seeds,
             ),
-            executor_fee,
+            amount,
+            ctx.accounts.mint.decimals,
         )?;
 
-        emit!(ArbitrageExecuted {
-            executor: ctx.accounts.executor.key(),
-            profit,
-            executor_fee,
-            vault_profit: profit - executor_fee,
+        let vault = &mut ctx.accounts.vault;
+        vault.flash_loan_amount = amount;
+        vault.flash_loan_fee = fee;
+
+        emit!(FlashLoanBorrowed {
+            vault: vault.key(),
+            borrower: ctx.accounts.borrower.key(),
+            amount,
+            fee,
         });
 
         Ok(())
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+    /// Repay the outstanding flash loan: `amount` must be the borrowed
+    /// principal, and the vault must receive it plus the fee
+    pub fn flash_repay(ctx: Context<FlashRepay>, amount: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        require!(
+            vault.flash_loan_amount > 0 && amount == vault.flash_loan_amount,
+            ErrorCode::FlashLoanNotRepaid
+        );
+        require_top_level()?;
+
+        let fee = vault.flash_loan_fee;
+        let owed = amount.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+        let vault_balance = ctx.accounts.vault_token.amount;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.borrower_token.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault_token.to_account_info(),
+                    authority: ctx.accounts.borrower.to_account_info(),
+                },
+            ),
+            owed,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        // Measured, so a Token-2022 transfer fee cannot shortchange the vault
+        ctx.accounts.vault_token.reload()?;
+        let received = ctx.accounts.vault_token.amount
+            .checked_sub(vault_balance)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(received >= owed, ErrorCode::FlashLoanNotRepaid);
+
         let vault = &mut ctx.accounts.vault;
-        let user_position = &mut ctx.accounts.user_position;
+        vault.flash_loan_amount = 0;
+        vault.flash_loan_fee = 0;
 
-        require!(user_position.shares >= shares, ErrorCode::InsufficientShares);
+        emit!(FlashLoanRepaid {
+            vault: vault.key(),
+            borrower: ctx.accounts.borrower.key(),
+            amount,
+            fee,
+        });
 
-        let vault_balance = ctx.accounts.vault_token.amount;
+        Ok(())
+    }
+
+    /// Shares `deposit(assets)` would mint right now, before any transfer fee
+    pub fn preview_deposit(ctx: Context<VaultView>, assets: u64) -> Result<u64> {
+        let (total_shares, total_assets) = ctx.accounts.totals()?;
+        shares_for_assets(assets, total_shares, total_assets, Rounding::Down)
+    }
+
+    /// Shares that must be burned to withdraw `assets`, rounded up
+    pub fn preview_withdraw(ctx: Context<VaultView>, assets: u64) -> Result<u64> {
+        let (total_shares, total_assets) = ctx.accounts.totals()?;
+        shares_for_assets(assets, total_shares, total_assets, Rounding::Up)
+    }
+
+    /// Shares worth `assets` at the current price, rounded down
+    pub fn convert_to_shares(ctx: Context<VaultView>, assets: u64) -> Result<u64> {
+        let (total_shares, total_assets) = ctx.accounts.totals()?;
+        shares_for_assets(assets, total_shares, total_assets, Rounding::Down)
+    }
+
+    /// Tokens `shares` are worth at the current price, rounded down
+    pub fn convert_to_assets(ctx: Context<VaultView>, shares: u64) -> Result<u64> {
+        let (total_shares, total_assets) = ctx.accounts.totals()?;
+        assets_for_shares(shares, total_shares, total_assets, Rounding::Down)
+    }
+
+    /// Set the flash loan fee (authority only)
+    pub fn set_flash_fee(ctx: Context<UpdateVaultConfig>, flash_fee_bps: u16) -> Result<()> {
+        require!(flash_fee_bps <= MAX_FLASH_FEE_BPS, ErrorCode::InvalidFlashFee);
+
+        let vault = &mut ctx.accounts.vault;
+        let old_flash_fee_bps = vault.flash_fee_bps;
+        vault.flash_fee_bps = flash_fee_bps;
+
+        emit!(FlashFeeUpdated {
+            vault: vault.key(),
+            old_flash_fee_bps,
+            new_flash_fee_bps: flash_fee_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Set how arbitrage profit is split between the executor, the protocol
+    /// and depositors (authority only)
+    pub fn update_fees(
+        ctx: Context<UpdateVaultConfig>,
+        executor_fee_bps: u16,
+        protocol_fee_bps: u16,
+        protocol_fee_recipient: Pubkey,
+    ) -> Result<()> {
+        validate_fees(executor_fee_bps, protocol_fee_bps)?;
+        // A protocol cut needs somewhere to go
+        require!(
+            protocol_fee_bps == 0 || protocol_fee_recipient != Pubkey::default(),
+            ErrorCode::MissingFeeRecipient
+        );
+
+        let vault = &mut ctx.accounts.vault;
+        vault.executor_fee_bps = executor_fee_bps;
+        vault.protocol_fee_bps = protocol_fee_bps;
+        vault.protocol_fee_recipient = protocol_fee_recipient;
+
+        emit!(FeesUpdated {
+            vault: vault.key(),
+            executor_fee_bps,
+            protocol_fee_bps,
+            protocol_fee_recipient,
+        });
+
+        Ok(())
+    }
+
+    /// Redeem a whole position in the original single `["vault"]` vault for
+    /// its part of that vault's WSOL, rounded down, and close the position
+    pub fn legacy_withdraw(ctx: Context<LegacyWithdraw>) -> Result<()> {
+        let legacy_info = ctx.accounts.legacy_vault.to_account_info();
+        let offset = Vault::LEGACY_TOTAL_SHARES_OFFSET;
+        let total_shares = {
+            let data = legacy_info.try_borrow_data()?;
+            require!(
+                data.len() >= Vault::LEGACY_LEN && data[..8] == *Vault::DISCRIMINATOR,
+                ErrorCode::InvalidLegacyVault
+            );
+            u64::from_le_bytes(
+                data[offset..offset + 8]
+                    .try_into()
+                    .map_err(|_| ErrorCode::InvalidLegacyVault)?,
+            )
+        };
+
+        let shares = ctx.accounts.user_position.shares;
+        require!(shares > 0 && shares <= total_shares, ErrorCode::InsufficientShares);
 
-        let amount = if shares == vault.total_shares {
+        let vault_balance = ctx.accounts.vault_token.amount;
+        let amount = if shares == total_shares {
             vault_balance
         } else {
-            let numerator = (shares as u128)
+            let amount = (shares as u128)
                 .checked_mul(vault_balance as u128)
-                .ok_or(ErrorCode::MathOverflow)?;
-            let amount_u128 = numerator
-                .checked_div(vault.total_shares as u128)
-                .ok_or(ErrorCode::MathOverflow)?;
-            amount_u128.try_into()
-                .map_err(|_| ErrorCode::MathOverflow)?
+                .ok_or(ErrorCode::MathOverflow)?
+                / total_shares as u128;
+            u64::try_from(amount).map_err(|_| ErrorCode::MathOverflow)?
         };
 
-        let vault_bump = vault.bump;
-        let seeds = &[b"vault".as_ref(), &[vault_bump]];
+        let seeds = &[b"vault".as_ref(), &[ctx.bumps.legacy_vault]];
         let signer_seeds = &[&seeds[..]];
 
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.vault_token.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.user_token.to_account_info(),
-                    authority: vault.to_account_info(),
+                    authority: legacy_info.clone(),
                 },
                 signer_seeds,
             ),
             amount,
+            ctx.accounts.mint.decimals,
         )?;
 
-        user_position.shares = user_position.shares.checked_sub(shares)
-            .ok_or(ErrorCode::MathOverflow)?;
-        vault.total_shares = vault.total_shares.checked_sub(shares)
-            .ok_or(ErrorCode::MathOverflow)?;
+        legacy_info.try_borrow_mut_data()?[offset..offset + 8]
+            .copy_from_slice(&(total_shares - shares).to_le_bytes());
 
         emit!(Withdrawn {
+            vault: legacy_info.key(),
             user: ctx.accounts.user.key(),
             amount,
             shares,
@@ -208,68 +595,161 @@ pub mod vault {
     }
 }
 
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = VaultConfig::LEN,
+        seeds = [b"vault_config"],
+        bump
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Vault>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ ErrorCode::InvalidAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault_config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    pub admin: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct InitializeVault<'info> {
+    #[account(
+        seeds = [b"vault_config"],
+        bump = config.bump,
+        constraint = config.admin == authority.key() @ ErrorCode::InvalidAuthority
+    )]
+    pub config: Account<'info, VaultConfig>,
+
+    /// Base asset of the vault
+    pub mint: InterfaceAccount<'info, Mint>,
+
     #[account(
         init,
         payer = authority,
         space = Vault::LEN,
-        seeds = [b"vault"],
+        seeds = [b"vault", mint.key().as_ref()],
         bump
     )]
     pub vault: Account<'info, Vault>,
+
+    /// Canonical token account holding the vault's assets
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault_token", vault.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault,
+        token::token_program = token_program
+    )]
+    pub vault_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Transferable vault shares, minted and burned by the vault
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"share_mint", vault.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = vault,
+        mint::token_program = share_token_program,
+        extensions::metadata_pointer::authority = authority,
+        extensions::metadata_pointer::metadata_address = share_mint
+    )]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    /// CHECK: The swap router program that this vault will use (verified through has_one)
+    /// CHECK: The swap router program that this vault will use
+    #[account(address = swap_router::ID @ ErrorCode::InvalidSwapRouter)]
     pub swap_router: UncheckedAccount<'info>,
     
+    pub token_program: Interface<'info, TokenInterface>,
+    pub share_token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    #[account(mut, seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
 
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = UserPosition::LEN,
-        seeds = [b"position", user.key().as_ref()],
-        bump
-    )]
-    pub user_position: Account<'info, UserPosition>,
-
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(mut)]
-    pub user_token: Account<'info, TokenAccount>,
+    #[account(mut, constraint = user_token.mint == vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub user_token: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
-    pub vault_token: Account<'info, TokenAccount>,
+    #[account(mut, address = vault.token_account @ ErrorCode::InvalidVaultTokenAccount)]
+    pub vault_token: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    #[account(address = vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, address = vault.share_mint @ ErrorCode::InvalidShareMint)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Receives the minted shares; any holder may be credited
+    #[account(mut, token::mint = share_mint, token::token_program = share_token_program)]
+    pub user_share_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub share_token_program: Program<'info, Token2022>,
 }
 
 #[derive(Accounts)]
 pub struct ExecuteArbitrage<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    #[account(mut, seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
 
-    #[account(mut)]
-    pub vault_token: Account<'info, TokenAccount>,
+    #[account(mut, address = vault.token_account @ ErrorCode::InvalidVaultTokenAccount)]
+    pub vault_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
     pub executor: Signer<'info>,
 
-    #[account(mut)]
-    pub executor_token: Account<'info, TokenAccount>,
+    /// Receives the executor fee, in the vault's own asset
+    #[account(mut, constraint = executor_token.mint == vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub executor_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the protocol fee, owned by vault.protocol_fee_recipient;
+    /// only required when the profit yields a protocol fee
+    #[account(
+        mut,
+        constraint = protocol_fee_token.mint == vault.mint @ ErrorCode::InvalidTokenMint,
+        constraint = protocol_fee_token.owner == vault.protocol_fee_recipient @ ErrorCode::InvalidFeeRecipient
+    )]
+    pub protocol_fee_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     /// CHECK: Verified against vault.swap_router
+    #[account(address = vault.swap_router @ ErrorCode::InvalidSwapRouter)]
     pub swap_router_program: UncheckedAccount<'info>,
 
     /// CHECK: Router state PDA from swap router program
@@ -279,33 +759,199 @@ pub struct ExecuteArbitrage<'info> {
     /// CHECK: Jupiter V6 program ID
     pub jupiter_program: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
-    #[account(mut, seeds = [b"vault"], bump = vault.bump)]
+    #[account(mut, seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, address = vault.share_mint @ ErrorCode::InvalidShareMint)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Shares to burn, owned by `user`
+    #[account(
+        mut,
+        token::mint = share_mint,
+        token::authority = user,
+        token::token_program = share_token_program
+    )]
+    pub user_share_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::InvalidVaultTokenAccount)]
+    pub vault_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, constraint = user_token.mint == vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub user_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub share_token_program: Program<'info, Token2022>,
+}
+
+impl Withdraw<'_> {
+    /// Burn `shares` from the user and send them `amount` from the vault
+    fn redeem(&mut self, shares: u64, amount: u64) -> Result<()> {
+        require!(self.user_share_token.amount >= shares, ErrorCode::InsufficientShares);
+
+        token_interface::burn(
+            CpiContext::new(
+                self.share_token_program.to_account_info(),
+                Burn {
+                    mint: self.share_mint.to_account_info(),
+                    from: self.user_share_token.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        let seeds = &[b"vault".as_ref(), self.vault.mint.as_ref(), &[self.vault.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_token.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.user_token.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            self.mint.decimals,
+        )?;
+
+        self.vault.total_shares = self.vault.total_shares.checked_sub(shares)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(Withdrawn {
+            vault: self.vault.key(),
+            user: self.user.key(),
+            amount,
+            shares,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct FlashBorrow<'info> {
+    #[account(mut, seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
     pub vault: Account<'info, Vault>,
 
+    #[account(mut, address = vault.token_account @ ErrorCode::InvalidVaultTokenAccount)]
+    pub vault_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub borrower: Signer<'info>,
+
+    #[account(mut, token::mint = mint)]
+    pub borrower_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Instructions sysvar, used to find the matching flash_repay
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+// `vault` and `vault_token` must stay the first two accounts: flash_borrow
+// matches them by position
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    #[account(mut, seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::InvalidVaultTokenAccount)]
+    pub vault_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = vault.mint @ ErrorCode::InvalidTokenMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub borrower: Signer<'info>,
+
+    #[account(mut, token::mint = mint)]
+    pub borrower_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVaultConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        has_one = authority @ ErrorCode::InvalidAuthority
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VaultView<'info> {
+    #[account(seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.token_account @ ErrorCode::InvalidVaultTokenAccount)]
+    pub vault_token: InterfaceAccount<'info, TokenAccount>,
+}
+
+impl VaultView<'_> {
+    /// Outstanding shares and the vault balance; a lent-out balance would
+    /// understate the share price
+    fn totals(&self) -> Result<(u64, u64)> {
+        require!(self.vault.flash_loan_amount == 0, ErrorCode::FlashLoanActive);
+        Ok((self.vault.total_shares, self.vault_token.amount))
+    }
+}
+
+#[derive(Accounts)]
+pub struct LegacyWithdraw<'info> {
+    /// CHECK: Uses the original layout, so it is read manually.
+    /// Address and owner are enforced here; discriminator in the handler.
+    #[account(mut, seeds = [b"vault"], bump, owner = crate::ID)]
+    pub legacy_vault: UncheckedAccount<'info>,
+
     #[account(
         mut,
         seeds = [b"position", user.key().as_ref()],
         bump,
-        has_one = owner
+        constraint = user_position.owner == user.key() @ ErrorCode::InvalidAuthority,
+        close = user
     )]
     pub user_position: Account<'info, UserPosition>,
 
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(mut)]
-    pub vault_token: Account<'info, TokenAccount>,
+    /// The original vault's WSOL associated token account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = legacy_vault,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
-    pub user_token: Account<'info, TokenAccount>,
+    #[account(address = WSOL_MINT @ ErrorCode::InvalidTokenMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(mut, token::mint = mint)]
+    pub user_token: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: Verified through has_one
-    pub owner: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
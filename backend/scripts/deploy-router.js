@@ -39,6 +39,38 @@ async function deployRouter() {
     console.log("Router State:", routerStatePda.toString());
     console.log("Fee Rate:", feeRate, "basis points");
 
+    // Allow the vault program to execute vault-signed swaps
+    const vaultProgram = anchor.workspace.Vault;
+    const registerTx = await program.methods
+      .registerVaultProgram(vaultProgram.programId)
+      .accounts({
+        routerState: routerStatePda,
+        authority: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    console.log("Vault program registered:", vaultProgram.programId.toString());
+    console.log("Transaction signature:", registerTx);
+
+    // Create the DEX registry (seeded with Jupiter V6)
+    const [dexRegistryPda] = await PublicKey.findProgramAddress(
+      [Buffer.from("dex_registry")],
+      program.programId
+    );
+
+    const registryTx = await program.methods
+      .initializeDexRegistry()
+      .accounts({
+        routerState: routerStatePda,
+        dexRegistry: dexRegistryPda,
+        authority: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log("DEX Registry:", dexRegistryPda.toString());
+    console.log("Transaction signature:", registryTx);
+
     // Fetch and display the router state
     const routerAccount = await program.account.routerState.fetch(routerStatePda);
     console.log("Router Account Data:");
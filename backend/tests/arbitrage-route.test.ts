@@ -0,0 +1,278 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SwapRouter } from "../target/types/swap_router";
+import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("Arbitrage Route Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+
+  let routerState: anchor.web3.PublicKey;
+  let mintX: anchor.web3.PublicKey;
+  let mintY: anchor.web3.PublicKey;
+  let mintZ: anchor.web3.PublicKey;
+  const userTokens = new Map<string, anchor.web3.PublicKey>();
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, swapRouterProgram.programId)[0];
+  const sorted = (m1: anchor.web3.PublicKey, m2: anchor.web3.PublicKey) =>
+    Buffer.compare(m1.toBuffer(), m2.toBuffer()) < 0 ? [m1, m2] : [m2, m1];
+  const userToken = (mint: anchor.web3.PublicKey) => userTokens.get(mint.toBase58())!;
+
+  // [pool, vault_a, vault_b, mint_a, mint_b] for the pair
+  const poolAccounts = (m1: anchor.web3.PublicKey, m2: anchor.web3.PublicKey) => {
+    const [mintA, mintB] = sorted(m1, m2);
+    const pool = pda([Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer()]);
+    return {
+      pool,
+      mintA,
+      mintB,
+      vaultA: pda([Buffer.from("pool_vault"), pool.toBuffer(), mintA.toBuffer()]),
+      vaultB: pda([Buffer.from("pool_vault"), pool.toBuffer(), mintB.toBuffer()]),
+      lpMint: pda([Buffer.from("pool_lp"), pool.toBuffer()]),
+    };
+  };
+
+  const createPool = async (m1: anchor.web3.PublicKey, amount1: number, m2: anchor.web3.PublicKey, amount2: number) => {
+    const accounts = poolAccounts(m1, m2);
+    await swapRouterProgram.methods
+      .createPool()
+      .accounts({
+        ...accounts,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const [amountA, amountB] = accounts.mintA.equals(m1) ? [amount1, amount2] : [amount2, amount1];
+    const userLpToken = await createAccount(provider.connection, payer, accounts.lpMint, user);
+    await swapRouterProgram.methods
+      .addLiquidity(new anchor.BN(amountA), new anchor.BN(amountB), new anchor.BN(0))
+      .accounts({
+        ...accounts,
+        user,
+        userTokenA: userToken(accounts.mintA),
+        userTokenB: userToken(accounts.mintB),
+        userLpToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  };
+
+  const poolRange = (m1: anchor.web3.PublicKey, m2: anchor.web3.PublicKey) => {
+    const { pool, vaultA, vaultB, mintA, mintB } = poolAccounts(m1, m2);
+    return [
+      { pubkey: pool, isSigner: false, isWritable: false },
+      { pubkey: vaultA, isSigner: false, isWritable: true },
+      { pubkey: vaultB, isSigner: false, isWritable: true },
+      { pubkey: mintA, isSigner: false, isWritable: false },
+      { pubkey: mintB, isSigner: false, isWritable: false },
+    ];
+  };
+
+  const poolHop = (inputMint: anchor.web3.PublicKey, outputMint: anchor.web3.PublicKey, accountsStart: number, amount: any) => ({
+    adapter: { nativePool: {} },
+    accountsStart,
+    accountsLen: 5,
+    inputMint,
+    outputMint,
+    amount,
+    data: Buffer.alloc(0),
+  });
+
+  const swapAccounts = (source: anchor.web3.PublicKey, destination: anchor.web3.PublicKey) => ({
+    swap: {
+      routerState,
+      user,
+      userSourceToken: source,
+      userDestinationToken: destination,
+      outputMint: mintX,
+      feeTreasury: pda([Buffer.from("fee_treasury"), mintX.toBuffer()]),
+      inputMintStats: pda([Buffer.from("mint_stats"), mintX.toBuffer()]),
+      outputMintStats: pda([Buffer.from("mint_stats"), mintX.toBuffer()]),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    },
+    dexRegistry: pda([Buffer.from("dex_registry")]),
+  });
+
+  // X -> Y -> Z -> X; remaining_accounts: [Y, Z, pool XY, pool YZ, pool ZX]
+  const triangle = (amountIn: number, minProfit: number) =>
+    swapRouterProgram.methods
+      .executeArbitrageRoute(
+        [
+          poolHop(mintX, mintY, 2, { exact: { 0: new anchor.BN(amountIn) } }),
+          poolHop(mintY, mintZ, 7, { allOfPrevious: {} }),
+          poolHop(mintZ, mintX, 12, { allOfPrevious: {} }),
+        ],
+        new anchor.BN(minProfit)
+      )
+      .accounts(swapAccounts(userToken(mintX), userToken(mintX)))
+      .remainingAccounts([
+        { pubkey: userToken(mintY), isSigner: false, isWritable: true },
+        { pubkey: userToken(mintZ), isSigner: false, isWritable: true },
+        ...poolRange(mintX, mintY),
+        ...poolRange(mintY, mintZ),
+        ...poolRange(mintZ, mintX),
+      ])
+      .rpc();
+
+  const balance = async (account: anchor.web3.PublicKey) =>
+    BigInt((await getAccount(provider.connection, account)).amount);
+
+  before(async () => {
+    routerState = pda([Buffer.from("router_state")]);
+
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+    try {
+      await swapRouterProgram.methods
+        .initializeDexRegistry()
+        .accounts({
+          routerState,
+          dexRegistry: pda([Buffer.from("dex_registry")]),
+          authority: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  DEX registry already initialized");
+    }
+
+    mintX = await createMint(provider.connection, payer, user, null, 6);
+    mintY = await createMint(provider.connection, payer, user, null, 6);
+    mintZ = await createMint(provider.connection, payer, user, null, 6);
+    for (const mint of [mintX, mintY, mintZ]) {
+      const account = await createAccount(provider.connection, payer, mint, user);
+      userTokens.set(mint.toBase58(), account);
+      await mintTo(provider.connection, payer, mint, account, user, 1_000_000_000);
+
+      await swapRouterProgram.methods
+        .initializeFeeTreasury()
+        .accounts({
+          routerState,
+          feeTreasury: pda([Buffer.from("fee_treasury"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      await swapRouterProgram.methods
+        .initializeMintStats()
+        .accounts({
+          mintStats: pda([Buffer.from("mint_stats"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    // X = Y and Y = Z, but the Z/X pool prices 1 Z at 2 X
+    await createPool(mintX, 100_000_000, mintY, 100_000_000);
+    await createPool(mintY, 100_000_000, mintZ, 100_000_000);
+    await createPool(mintZ, 100_000_000, mintX, 200_000_000);
+  });
+
+  it("Executes a profitable triangle and charges the fee on the profit", async () => {
+    const feeTreasuryX = pda([Buffer.from("fee_treasury"), mintX.toBuffer()]);
+    const treasuryBefore = await balance(feeTreasuryX);
+    const xBefore = await balance(userToken(mintX));
+    const yBefore = await balance(userToken(mintY));
+    const zBefore = await balance(userToken(mintZ));
+    const mintStatsX = pda([Buffer.from("mint_stats"), mintX.toBuffer()]);
+    const countBefore = (await swapRouterProgram.account.mintStats.fetch(mintStatsX)).swapCount.toNumber();
+
+    await triangle(1_000_000, 500_000);
+
+    const profit = (await balance(userToken(mintX))) - xBefore;
+    const fee = (await balance(feeTreasuryX)) - treasuryBefore;
+    assert.ok(profit >= 500_000n);
+    assert.ok(fee > 0n);
+    // 30 bps of the gross profit
+    assert.equal(fee, ((profit + fee) * 30n) / 10_000n);
+    // Intermediate legs are fully consumed
+    assert.equal(await balance(userToken(mintY)), yBefore);
+    assert.equal(await balance(userToken(mintZ)), zBefore);
+    // Same mint in and out: counted once, not once per side
+    const countAfter = (await swapRouterProgram.account.mintStats.fetch(mintStatsX)).swapCount.toNumber();
+    assert.equal(countAfter - countBefore, 1);
+    console.log("✅ Profit:", profit.toString(), "Fee:", fee.toString());
+  });
+
+  it("Reverts when the profit is below min_profit", async () => {
+    const xBefore = await balance(userToken(mintX));
+    try {
+      await triangle(1_000_000, 10_000_000);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Arbitrage profit below the minimum");
+      console.log("✅ Correctly rejected");
+    }
+    assert.equal(await balance(userToken(mintX)), xBefore);
+  });
+
+  it("Reverts a losing round trip", async () => {
+    try {
+      await swapRouterProgram.methods
+        .executeArbitrageRoute(
+          [
+            poolHop(mintX, mintY, 1, { exact: { 0: new anchor.BN(1_000_000) } }),
+            poolHop(mintY, mintX, 1, { allOfPrevious: {} }),
+          ],
+          new anchor.BN(0)
+        )
+        .accounts(swapAccounts(userToken(mintX), userToken(mintX)))
+        .remainingAccounts([
+          { pubkey: userToken(mintY), isSigner: false, isWritable: true },
+          ...poolRange(mintX, mintY),
+        ])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Arbitrage profit below the minimum");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a route that does not return to the source account", async () => {
+    const otherX = await createAccount(provider.connection, payer, mintX, user, anchor.web3.Keypair.generate());
+    try {
+      await swapRouterProgram.methods
+        .executeArbitrageRoute(
+          [
+            poolHop(mintX, mintY, 1, { exact: { 0: new anchor.BN(1_000_000) } }),
+            poolHop(mintY, mintX, 1, { allOfPrevious: {} }),
+          ],
+          new anchor.BN(0)
+        )
+        .accounts(swapAccounts(userToken(mintX), otherX))
+        .remainingAccounts([
+          { pubkey: userToken(mintY), isSigner: false, isWritable: true },
+          ...poolRange(mintX, mintY),
+        ])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid route");
+      console.log("✅ Correctly rejected");
+    }
+  });
+});
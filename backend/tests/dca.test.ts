@@ -0,0 +1,372 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SwapRouter } from "../target/types/swap_router";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { MockPool, createMockPool, mockRouteAccounts, mockRoutePayload } from "./utils/jupiter";
+import { createTransferFeeMint, transferFeeOf } from "./utils/token2022";
+
+describe("DCA Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const JUPITER_PROGRAM_ID = new anchor.web3.PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+  const ROUTE_DISCRIMINATOR = Buffer.from([229, 23, 203, 151, 122, 227, 173, 42]);
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+
+  let routerState: anchor.web3.PublicKey;
+  let inputMint: anchor.web3.PublicKey;
+  let outputMint: anchor.web3.PublicKey;
+  let userSource: anchor.web3.PublicKey;
+  let userDestination: anchor.web3.PublicKey;
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, swapRouterProgram.programId)[0];
+
+  const dcaAccounts = (dcaId: number) => {
+    const id = Buffer.alloc(8);
+    id.writeBigUInt64LE(BigInt(dcaId));
+    const dca = pda([Buffer.from("dca"), user.toBuffer(), id]);
+    return {
+      dca,
+      inputEscrow: pda([Buffer.from("dca_escrow"), dca.toBuffer()]),
+      outputEscrow: pda([Buffer.from("dca_output"), dca.toBuffer()]),
+      inputMint,
+      outputMint,
+      inputTokenProgram: TOKEN_PROGRAM_ID,
+      outputTokenProgram: TOKEN_PROGRAM_ID,
+    };
+  };
+
+  const createDca = (dcaId: number, total: number, perCycle: number, interval: number, minOut: number) =>
+    swapRouterProgram.methods
+      .createDca(
+        new anchor.BN(dcaId),
+        new anchor.BN(total),
+        new anchor.BN(perCycle),
+        new anchor.BN(interval),
+        new anchor.BN(minOut)
+      )
+      .accounts({
+        routerState,
+        ...dcaAccounts(dcaId),
+        ownerSourceToken: userSource,
+        ownerDestinationToken: userDestination,
+        owner: user,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+  // route(route_plan, in_amount, quoted_out_amount, slippage_bps, platform_fee_bps)
+  const routePayload = (inAmount: number, quotedOut: number, slippageBps: number) => {
+    const tail = Buffer.alloc(19);
+    tail.writeBigUInt64LE(BigInt(inAmount), 0);
+    tail.writeBigUInt64LE(BigInt(quotedOut), 8);
+    tail.writeUInt16LE(slippageBps, 16);
+    tail.writeUInt8(0, 18);
+    const plan = Buffer.concat([Buffer.from([1, 0, 0, 0]), Buffer.from([0, 100, 0, 1])]);
+    return Buffer.concat([ROUTE_DISCRIMINATOR, plan, tail]);
+  };
+
+  before(async () => {
+    routerState = pda([Buffer.from("router_state")]);
+
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+
+    inputMint = await createMint(provider.connection, payer, user, null, 6);
+    outputMint = await createMint(provider.connection, payer, user, null, 6);
+    userSource = await createAccount(provider.connection, payer, inputMint, user);
+    userDestination = await createAccount(provider.connection, payer, outputMint, user);
+    await mintTo(provider.connection, payer, inputMint, userSource, user, 1_000_000_000);
+
+    await swapRouterProgram.methods
+      .initializeFeeTreasury()
+      .accounts({
+        routerState,
+        feeTreasury: pda([Buffer.from("fee_treasury"), outputMint.toBuffer()]),
+        mint: outputMint,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Rejects a per-cycle amount above the total", async () => {
+    try {
+      await createDca(1, 10_000_000, 20_000_000, 3600, 5_000_000);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid DCA schedule");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a zero interval", async () => {
+    try {
+      await createDca(1, 10_000_000, 1_000_000, 0, 500_000);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid DCA schedule");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a schedule without a price floor", async () => {
+    try {
+      await createDca(1, 10_000_000, 1_000_000, 3600, 0);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid DCA schedule");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Creates a DCA schedule due immediately", async () => {
+    await createDca(1, 40_000_000, 10_000_000, 3600, 5_000_000);
+
+    const { dca, inputEscrow } = dcaAccounts(1);
+    const schedule = await swapRouterProgram.account.dcaSchedule.fetch(dca);
+    assert.ok(schedule.owner.equals(user));
+    assert.ok(schedule.destination.equals(userDestination));
+    assert.equal(schedule.totalAmount.toNumber(), 40_000_000);
+    assert.equal(schedule.amountPerCycle.toNumber(), 10_000_000);
+    assert.equal(schedule.intervalSecs.toNumber(), 3600);
+    assert.equal(schedule.minOutputPerCycle.toNumber(), 5_000_000);
+    assert.equal(schedule.cyclesExecuted, 0);
+    assert.isAtMost(schedule.nextCycleTs.toNumber(), Math.floor(Date.now() / 1000) + 5);
+    assert.equal((await getAccount(provider.connection, inputEscrow)).amount.toString(), "40000000");
+    console.log("✅ DCA created");
+  });
+
+  it("Rejects a cycle payload selling more than one slice", async () => {
+    const keeper = anchor.web3.Keypair.generate();
+    const keeperToken = await createAccount(provider.connection, payer, outputMint, keeper.publicKey);
+    const { inputEscrow, outputEscrow } = dcaAccounts(1);
+
+    try {
+      await swapRouterProgram.methods
+        .executeDcaCycle(routePayload(40_000_000, 20_000_000, 50))
+        .accounts({
+          routerState,
+          ...dcaAccounts(1),
+          ownerDestinationToken: userDestination,
+          feeTreasury: pda([Buffer.from("fee_treasury"), outputMint.toBuffer()]),
+          keeper: keeper.publicKey,
+          keeperToken,
+          jupiterProgram: JUPITER_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          [inputEscrow, outputEscrow].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+        )
+        .signers([keeper])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid swap amount");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Only the owner can close a schedule", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    try {
+      await swapRouterProgram.methods
+        .closeDca()
+        .accounts({
+          ...dcaAccounts(1),
+          ownerSourceToken: userSource,
+          ownerDestinationToken: userDestination,
+          owner: stranger.publicKey,
+        })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.ok(error);
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Closes a DCA schedule and refunds the unspent input", async () => {
+    const { dca, inputEscrow, outputEscrow } = dcaAccounts(1);
+    const before = BigInt((await getAccount(provider.connection, userSource)).amount);
+
+    await swapRouterProgram.methods
+      .closeDca()
+      .accounts({
+        ...dcaAccounts(1),
+        ownerSourceToken: userSource,
+        ownerDestinationToken: userDestination,
+        owner: user,
+      })
+      .rpc();
+
+    const after = BigInt((await getAccount(provider.connection, userSource)).amount);
+    assert.equal((after - before).toString(), "40000000");
+    for (const closed of [dca, inputEscrow, outputEscrow]) {
+      assert.isNull(await provider.connection.getAccountInfo(closed));
+    }
+    console.log("✅ DCA closed and refunded");
+  });
+
+  // Escrows of transfer-fee mints hold withheld fees, which are harvested
+  // to the mint when the schedule is closed
+  describe("Transfer-fee mints", () => {
+    const FEE_BPS = 100;
+    const TOTAL = BigInt(10_000_000);
+    const PER_CYCLE = BigInt(5_000_000);
+    const QUOTED_OUT = BigInt(8_000_000);
+    const keeper = anchor.web3.Keypair.generate();
+
+    let feeInputMint: anchor.web3.PublicKey;
+    let feeOutputMint: anchor.web3.PublicKey;
+    let ownerSource: anchor.web3.PublicKey;
+    let ownerDestination: anchor.web3.PublicKey;
+    let keeperToken: anchor.web3.PublicKey;
+    let pool: MockPool;
+
+    const feeDcaAccounts = (dcaId: number) => ({
+      ...dcaAccounts(dcaId),
+      inputMint: feeInputMint,
+      outputMint: feeOutputMint,
+      inputTokenProgram: TOKEN_2022_PROGRAM_ID,
+      outputTokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+
+    const balance = async (account: anchor.web3.PublicKey) =>
+      (await getAccount(provider.connection, account, undefined, TOKEN_2022_PROGRAM_ID)).amount;
+
+    const executeCycle = (payload: Buffer) => {
+      const { dca, inputEscrow, outputEscrow } = feeDcaAccounts(10);
+      return swapRouterProgram.methods
+        .executeDcaCycle(payload)
+        .accounts({
+          routerState,
+          ...feeDcaAccounts(10),
+          ownerDestinationToken: ownerDestination,
+          feeTreasury: pda([Buffer.from("fee_treasury"), feeOutputMint.toBuffer()]),
+          keeper: keeper.publicKey,
+          keeperToken,
+          jupiterProgram: JUPITER_PROGRAM_ID,
+        })
+        .remainingAccounts(mockRouteAccounts(pool, dca, inputEscrow, outputEscrow))
+        .signers([keeper])
+        .rpc();
+    };
+
+    before(async () => {
+      feeInputMint = await createTransferFeeMint(provider, FEE_BPS);
+      feeOutputMint = await createTransferFeeMint(provider, FEE_BPS);
+      ownerSource = await createAccount(
+        provider.connection, payer, feeInputMint, user, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      ownerDestination = await createAccount(
+        provider.connection, payer, feeOutputMint, user, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      keeperToken = await createAccount(
+        provider.connection, payer, feeOutputMint, keeper.publicKey, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        provider.connection, payer, feeInputMint, ownerSource, user, TOTAL, [], undefined, TOKEN_2022_PROGRAM_ID
+      );
+      pool = await createMockPool(
+        provider, feeInputMint, feeOutputMint, BigInt(10) * QUOTED_OUT, TOKEN_2022_PROGRAM_ID, TOKEN_2022_PROGRAM_ID
+      );
+
+      await swapRouterProgram.methods
+        .initializeFeeTreasury()
+        .accounts({
+          routerState,
+          feeTreasury: pda([Buffer.from("fee_treasury"), feeOutputMint.toBuffer()]),
+          mint: feeOutputMint,
+          payer: user,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      await swapRouterProgram.methods
+        .createDca(
+          new anchor.BN(10),
+          new anchor.BN(TOTAL.toString()),
+          new anchor.BN(PER_CYCLE.toString()),
+          new anchor.BN(3600),
+          new anchor.BN(1)
+        )
+        .accounts({
+          routerState,
+          ...feeDcaAccounts(10),
+          ownerSourceToken: ownerSource,
+          ownerDestinationToken: ownerDestination,
+          owner: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    });
+
+    it("Rejects a keeper payload charging a platform fee", async () => {
+      try {
+        await executeCycle(mockRoutePayload(PER_CYCLE, QUOTED_OUT, 50, 10));
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        assert.include(error.error.errorMessage, "Jupiter platform fee not allowed");
+        console.log("✅ Correctly rejected");
+      }
+    });
+
+    it("Executes a cycle into the output escrow", async () => {
+      const before = await balance(ownerDestination);
+
+      await executeCycle(mockRoutePayload(PER_CYCLE, QUOTED_OUT));
+
+      assert.ok((await balance(ownerDestination)) > before);
+      const schedule = await swapRouterProgram.account.dcaSchedule.fetch(feeDcaAccounts(10).dca);
+      assert.equal(schedule.cyclesExecuted, 1);
+      assert.equal(schedule.totalSpent.toString(), PER_CYCLE.toString());
+      console.log("✅ Cycle executed");
+    });
+
+    it("Closes a schedule whose escrows hold withheld fees", async () => {
+      const { dca, inputEscrow, outputEscrow } = feeDcaAccounts(10);
+      const unspent = await balance(inputEscrow);
+      assert.equal(unspent, TOTAL - transferFeeOf(TOTAL, FEE_BPS) - PER_CYCLE);
+      const before = await balance(ownerSource);
+
+      await swapRouterProgram.methods
+        .closeDca()
+        .accounts({
+          ...feeDcaAccounts(10),
+          ownerSourceToken: ownerSource,
+          ownerDestinationToken: ownerDestination,
+          owner: user,
+        })
+        .rpc();
+
+      assert.equal((await balance(ownerSource)) - before, unspent - transferFeeOf(unspent, FEE_BPS));
+      for (const closed of [dca, inputEscrow, outputEscrow]) {
+        assert.isNull(await provider.connection.getAccountInfo(closed));
+      }
+      console.log("✅ DCA closed, escrows closed");
+    });
+  });
+});
@@ -0,0 +1,200 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { SwapRouter } from "../target/types/swap_router";
+import { TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, createMint, createAccount, mintTo, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { initializeVaultConfig, vaultConfigAddress } from "./utils/vault";
+
+describe("Flash Loan Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const vaultProgram = anchor.workspace.Vault as Program<Vault>;
+  const vaultConfig = vaultConfigAddress(vaultProgram);
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+
+  let vaultPda: anchor.web3.PublicKey;
+  let mint: anchor.web3.PublicKey;
+  let vaultToken: anchor.web3.PublicKey;
+  let shareMint: anchor.web3.PublicKey;
+  let borrowerToken: anchor.web3.PublicKey;
+
+  const loanAccounts = () => ({
+    vault: vaultPda,
+    vaultToken,
+    mint,
+    borrower: user,
+    borrowerToken,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  const borrowIx = (amount: number) =>
+    vaultProgram.methods
+      .flashBorrow(new anchor.BN(amount))
+      .accounts({ ...loanAccounts(), instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY })
+      .instruction();
+
+  const repayIx = (amount: number) =>
+    vaultProgram.methods
+      .flashRepay(new anchor.BN(amount))
+      .accounts(loanAccounts())
+      .instruction();
+
+  const balance = async (account: anchor.web3.PublicKey) =>
+    BigInt((await getAccount(provider.connection, account)).amount);
+
+  before(async () => {
+    await initializeVaultConfig(vaultProgram, provider);
+
+    mint = await createMint(provider.connection, payer, user, null, 6);
+    [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), mint.toBuffer()],
+      vaultProgram.programId
+    );
+    [vaultToken] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_token"), vaultPda.toBuffer()],
+      vaultProgram.programId
+    );
+    [shareMint] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("share_mint"), vaultPda.toBuffer()],
+      vaultProgram.programId
+    );
+
+    await vaultProgram.methods
+      .initializeVault("Flash Vault Share", "axFL", "")
+      .accounts({
+        config: vaultConfig,
+        mint,
+        vault: vaultPda,
+        vaultToken,
+        shareMint,
+        authority: user,
+        swapRouter: swapRouterProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    borrowerToken = await createAccount(provider.connection, payer, mint, user);
+    await mintTo(provider.connection, payer, mint, vaultToken, user, 1_000_000_000);
+    // Enough for the fees; the principal comes from the vault
+    await mintTo(provider.connection, payer, mint, borrowerToken, user, 10_000_000);
+  });
+
+  it("Rejects a flash fee above the cap", async () => {
+    try {
+      await vaultProgram.methods
+        .setFlashFee(1_001)
+        .accounts({ vault: vaultPda, authority: user })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Flash loan fee above the limit");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Only the vault authority can set the flash fee", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    try {
+      await vaultProgram.methods
+        .setFlashFee(10)
+        .accounts({ vault: vaultPda, authority: stranger.publicKey })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid vault authority");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Borrows and repays with the fee in one transaction", async () => {
+    await vaultProgram.methods
+      .setFlashFee(50)
+      .accounts({ vault: vaultPda, authority: user })
+      .rpc();
+
+    const vaultBefore = await balance(vaultToken);
+    const borrowerBefore = await balance(borrowerToken);
+
+    const tx = new anchor.web3.Transaction().add(await borrowIx(100_000_000), await repayIx(100_000_000));
+    await provider.sendAndConfirm(tx);
+
+    // 50 bps of 100 tokens
+    assert.equal((await balance(vaultToken)) - vaultBefore, 500_000n);
+    assert.equal(borrowerBefore - (await balance(borrowerToken)), 500_000n);
+    const vault = await vaultProgram.account.vault.fetch(vaultPda);
+    assert.equal(vault.flashLoanAmount.toNumber(), 0);
+    console.log("✅ Loan repaid with fee");
+  });
+
+  it("Rejects a borrow without a repay", async () => {
+    try {
+      await vaultProgram.methods
+        .flashBorrow(new anchor.BN(100_000_000))
+        .accounts({ ...loanAccounts(), instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Flash loan must be repaid");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a borrow whose repay is for a smaller amount", async () => {
+    const tx = new anchor.web3.Transaction().add(await borrowIx(100_000_000), await repayIx(1_000_000));
+    try {
+      await provider.sendAndConfirm(tx);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.ok(error.logs.some((log: string) => log.includes("FlashLoanNotRepaid")));
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a deposit while a loan is outstanding", async () => {
+    const userShareToken = await createAccount(
+      provider.connection,
+      payer,
+      shareMint,
+      user,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const depositIx = await vaultProgram.methods
+      .deposit(new anchor.BN(1_000_000), new anchor.BN(0))
+      .accounts({
+        vault: vaultPda,
+        user,
+        userToken: borrowerToken,
+        vaultToken,
+        mint,
+        shareMint,
+        userShareToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .instruction();
+    const tx = new anchor.web3.Transaction().add(
+      await borrowIx(100_000_000),
+      depositIx,
+      await repayIx(100_000_000)
+    );
+    try {
+      await provider.sendAndConfirm(tx);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.ok(error.logs.some((log: string) => log.includes("FlashLoanActive")));
+      console.log("✅ Correctly rejected");
+    }
+  });
+});
@@ -0,0 +1,352 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SwapRouter } from "../target/types/swap_router";
+import { TOKEN_PROGRAM_ID, createMint, createAccount } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("Jupiter Payload Validation Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const JUPITER_PROGRAM_ID = new anchor.web3.PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+  const ROUTE_DISCRIMINATOR = Buffer.from([229, 23, 203, 151, 122, 227, 173, 42]);
+  const EXACT_OUT_ROUTE_DISCRIMINATOR = Buffer.from([208, 51, 239, 151, 123, 43, 237, 92]);
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+
+  let routerState: anchor.web3.PublicKey;
+  let inputMint: anchor.web3.PublicKey;
+  let outputMint: anchor.web3.PublicKey;
+  let userSource: anchor.web3.PublicKey;
+  let userDestination: anchor.web3.PublicKey;
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, swapRouterProgram.programId)[0];
+
+  // route(route_plan, in_amount, quoted_out_amount, slippage_bps, platform_fee_bps)
+  // exact_out_route(route_plan, out_amount, quoted_in_amount, slippage_bps, platform_fee_bps)
+  const payload = (discriminator: Buffer, first: number | bigint, second: number, slippageBps: number) => {
+    const tail = Buffer.alloc(19);
+    tail.writeBigUInt64LE(BigInt(first), 0);
+    tail.writeBigUInt64LE(BigInt(second), 8);
+    tail.writeUInt16LE(slippageBps, 16);
+    tail.writeUInt8(0, 18);
+    // One opaque route plan step
+    const plan = Buffer.concat([Buffer.from([1, 0, 0, 0]), Buffer.from([0, 100, 0, 1])]);
+    return Buffer.concat([discriminator, plan, tail]);
+  };
+  const routePayload = (inAmount: number, quotedOut: number, slippageBps: number) =>
+    payload(ROUTE_DISCRIMINATOR, inAmount, quotedOut, slippageBps);
+
+  // Account layout of `route`
+  const routeAccounts = (source: anchor.web3.PublicKey) =>
+    [
+      TOKEN_PROGRAM_ID,
+      user,
+      source,
+      userDestination,
+      JUPITER_PROGRAM_ID,
+      outputMint,
+    ].map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }));
+
+  const swapAccounts = () => ({
+    swap: {
+      routerState,
+      user,
+      userSourceToken: userSource,
+      userDestinationToken: userDestination,
+      outputMint,
+      feeTreasury: pda([Buffer.from("fee_treasury"), outputMint.toBuffer()]),
+      inputMintStats: pda([Buffer.from("mint_stats"), inputMint.toBuffer()]),
+      outputMintStats: pda([Buffer.from("mint_stats"), outputMint.toBuffer()]),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    },
+    jupiterProgram: JUPITER_PROGRAM_ID,
+  });
+
+  before(async () => {
+    routerState = pda([Buffer.from("router_state")]);
+
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+    try {
+      await swapRouterProgram.methods
+        .initializeDexRegistry()
+        .accounts({
+          routerState,
+          dexRegistry: pda([Buffer.from("dex_registry")]),
+          authority: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  DEX registry already initialized");
+    }
+
+    inputMint = await createMint(provider.connection, payer, user, null, 6);
+    outputMint = await createMint(provider.connection, payer, user, null, 6);
+    userSource = await createAccount(provider.connection, payer, inputMint, user);
+    userDestination = await createAccount(provider.connection, payer, outputMint, user);
+
+    await swapRouterProgram.methods
+      .initializeFeeTreasury()
+      .accounts({
+        routerState,
+        feeTreasury: pda([Buffer.from("fee_treasury"), outputMint.toBuffer()]),
+        mint: outputMint,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    for (const mint of [inputMint, outputMint]) {
+      await swapRouterProgram.methods
+        .initializeMintStats()
+        .accounts({
+          mintStats: pda([Buffer.from("mint_stats"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  it("Sets the router slippage cap", async () => {
+    await swapRouterProgram.methods
+      .setMaxSlippage(100)
+      .accounts({ routerState, authority: user })
+      .rpc();
+
+    const state = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.equal(state.maxSlippageBps, 100);
+    console.log("✅ Max slippage set to 100 bps");
+  });
+
+  it("Rejects an unknown instruction discriminator", async () => {
+    try {
+      await swapRouterProgram.methods
+        .executeJupiterSwap(Buffer.from([1, 2, 3, 4, 5, 6, 7, 8, 9]), null, null, null, null)
+        .accounts(swapAccounts())
+        .remainingAccounts(routeAccounts(userSource))
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Unsupported Jupiter instruction");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects slippage above the router cap", async () => {
+    try {
+      await swapRouterProgram.methods
+        .executeJupiterSwap(routePayload(1_000, 2_000, 500), null, null, null, null)
+        .accounts(swapAccounts())
+        .remainingAccounts(routeAccounts(userSource))
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Slippage tolerance above the router limit");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects an input amount above max_amount_in", async () => {
+    try {
+      await swapRouterProgram.methods
+        .executeJupiterSwap(routePayload(1_000, 2_000, 50), null, new anchor.BN(999), null, null)
+        .accounts(swapAccounts())
+        .remainingAccounts(routeAccounts(userSource))
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Slippage tolerance exceeded");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a swap landing after valid_until_slot", async () => {
+    const slot = await provider.connection.getSlot();
+    try {
+      await swapRouterProgram.methods
+        .executeJupiterSwap(routePayload(1_000, 2_000, 50), null, null, new anchor.BN(slot - 1), null)
+        .accounts(swapAccounts())
+        .remainingAccounts(routeAccounts(userSource))
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Swap expired");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a swap landing after its deadline", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      await swapRouterProgram.methods
+        .executeJupiterSwap(routePayload(1_000, 2_000, 50), null, null, null, new anchor.BN(now - 60))
+        .accounts(swapAccounts())
+        .remainingAccounts(routeAccounts(userSource))
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Swap expired");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a payload spending from another token account", async () => {
+    const otherSource = await createAccount(
+      provider.connection,
+      payer,
+      inputMint,
+      user,
+      anchor.web3.Keypair.generate()
+    );
+
+    try {
+      await swapRouterProgram.methods
+        .executeJupiterSwap(routePayload(1_000, 2_000, 50), null, null, null, null)
+        .accounts(swapAccounts())
+        .remainingAccounts(routeAccounts(otherSource))
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Jupiter instruction accounts do not match");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a non exact-out payload on the exact-out path", async () => {
+    try {
+      await swapRouterProgram.methods
+        .executeExactOutSwap(routePayload(1_000, 2_000, 50), new anchor.BN(2_000), new anchor.BN(1_000))
+        .accounts({
+          ...swapAccounts(),
+          sourceMint: inputMint,
+          escrowAuthority: pda([Buffer.from("escrow_authority")]),
+          escrow: pda([Buffer.from("exact_out_escrow"), user.toBuffer(), inputMint.toBuffer()]),
+          sourceTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts(routeAccounts(userSource))
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Unsupported Jupiter instruction");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Requires the exact-out target grossed up by the router fee", async () => {
+    const { feeRateBps } = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.ok(feeRateBps > 0);
+    const exactOut = (outAmount: number | bigint) =>
+      swapRouterProgram.methods
+        .executeExactOutSwap(
+          payload(EXACT_OUT_ROUTE_DISCRIMINATOR, outAmount, 1_000, 50),
+          new anchor.BN(2_000),
+          new anchor.BN(1_000)
+        )
+        .accounts({
+          ...swapAccounts(),
+          sourceMint: inputMint,
+          escrowAuthority: pda([Buffer.from("escrow_authority")]),
+          escrow: pda([Buffer.from("exact_out_escrow"), user.toBuffer(), inputMint.toBuffer()]),
+          sourceTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts(routeAccounts(userSource))
+        .rpc();
+
+    // Asking Jupiter for exactly amount_out would leave the user short by the fee
+    try {
+      await exactOut(2_000);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Output delta does not match the exact-out target");
+      console.log("✅ Correctly rejected");
+    }
+
+    // The grossed-up target passes validation and only fails at the (absent) Jupiter CPI
+    const gross = (BigInt(2_000) * BigInt(10_000)) / BigInt(10_000 - feeRateBps);
+    try {
+      await exactOut(gross);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.notInclude(String(error), "Output delta does not match the exact-out target");
+      console.log("✅ Grossed-up target accepted:", gross.toString());
+    }
+  });
+
+  it("Rejects a Jupiter payload on the generic DEX path", async () => {
+    try {
+      await swapRouterProgram.methods
+        .executeDexSwap(routePayload(1_000, 2_000, 500), null, null)
+        .accounts({
+          swap: swapAccounts().swap,
+          dexRegistry: pda([Buffer.from("dex_registry")]),
+          dexProgram: JUPITER_PROGRAM_ID,
+        })
+        .remainingAccounts(routeAccounts(userSource))
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid DEX program");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Checks Jupiter route hops like a direct Jupiter swap", async () => {
+    const jupiterHop = (data: Buffer) => ({
+      adapter: { jupiter: {} },
+      accountsStart: 0,
+      accountsLen: 7,
+      inputMint,
+      outputMint,
+      amount: { exact: { 0: new anchor.BN(1_000) } },
+      data,
+    });
+    const route = (data: Buffer, source: anchor.web3.PublicKey) =>
+      swapRouterProgram.methods
+        .executeRoute([jupiterHop(data)], null)
+        .accounts({ swap: swapAccounts().swap, dexRegistry: pda([Buffer.from("dex_registry")]) })
+        .remainingAccounts([
+          { pubkey: JUPITER_PROGRAM_ID, isSigner: false, isWritable: false },
+          ...routeAccounts(source),
+        ])
+        .rpc();
+
+    const cases: [() => Promise<unknown>, string][] = [
+      [() => route(routePayload(1_000, 2_000, 500), userSource), "Slippage tolerance above the router limit"],
+      [() => route(routePayload(1_001, 2_000, 50), userSource), "Slippage tolerance exceeded"],
+      [() => route(routePayload(1_000, 2_000, 50), userDestination), "Jupiter instruction accounts do not match"],
+    ];
+    for (const [action, message] of cases) {
+      try {
+        await action();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        assert.include(error.error.errorMessage, message);
+      }
+    }
+    console.log("✅ Jupiter hops decoded and checked");
+  });
+
+  after(async () => {
+    await swapRouterProgram.methods
+      .setMaxSlippage(0)
+      .accounts({ routerState, authority: user })
+      .rpc();
+  });
+});
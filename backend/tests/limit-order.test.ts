@@ -0,0 +1,357 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SwapRouter } from "../target/types/swap_router";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { MockPool, createMockPool, mockRouteAccounts, mockRoutePayload } from "./utils/jupiter";
+import { createTransferFeeMint, transferFeeOf } from "./utils/token2022";
+
+describe("Limit Order Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const JUPITER_PROGRAM_ID = new anchor.web3.PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+  const EXACT_OUT_ROUTE_DISCRIMINATOR = Buffer.from([208, 51, 239, 151, 123, 43, 237, 92]);
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+
+  let routerState: anchor.web3.PublicKey;
+  let inputMint: anchor.web3.PublicKey;
+  let outputMint: anchor.web3.PublicKey;
+  let userSource: anchor.web3.PublicKey;
+  let userDestination: anchor.web3.PublicKey;
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, swapRouterProgram.programId)[0];
+
+  const orderAccounts = (orderId: number) => {
+    const id = Buffer.alloc(8);
+    id.writeBigUInt64LE(BigInt(orderId));
+    const order = pda([Buffer.from("limit_order"), user.toBuffer(), id]);
+    return {
+      order,
+      inputEscrow: pda([Buffer.from("limit_order_escrow"), order.toBuffer()]),
+      outputEscrow: pda([Buffer.from("limit_order_output"), order.toBuffer()]),
+      inputMint,
+      outputMint,
+      inputTokenProgram: TOKEN_PROGRAM_ID,
+      outputTokenProgram: TOKEN_PROGRAM_ID,
+    };
+  };
+
+  const placeOrder = (orderId: number, amount: number, minOut: number, expiry: number) =>
+    swapRouterProgram.methods
+      .placeLimitOrder(new anchor.BN(orderId), new anchor.BN(amount), new anchor.BN(minOut), new anchor.BN(expiry))
+      .accounts({
+        routerState,
+        ...orderAccounts(orderId),
+        ownerSourceToken: userSource,
+        ownerDestinationToken: userDestination,
+        owner: user,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+  const inOneHour = () => Math.floor(Date.now() / 1000) + 3600;
+
+  before(async () => {
+    routerState = pda([Buffer.from("router_state")]);
+
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+
+    inputMint = await createMint(provider.connection, payer, user, null, 6);
+    outputMint = await createMint(provider.connection, payer, user, null, 6);
+    userSource = await createAccount(provider.connection, payer, inputMint, user);
+    userDestination = await createAccount(provider.connection, payer, outputMint, user);
+    await mintTo(provider.connection, payer, inputMint, userSource, user, 1_000_000_000);
+
+    await swapRouterProgram.methods
+      .initializeFeeTreasury()
+      .accounts({
+        routerState,
+        feeTreasury: pda([Buffer.from("fee_treasury"), outputMint.toBuffer()]),
+        mint: outputMint,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("Rejects a keeper bounty above the cap", async () => {
+    try {
+      await swapRouterProgram.methods
+        .setKeeperBounty(1_001)
+        .accounts({ routerState, authority: user })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Keeper bounty above the router limit");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Places a limit order with the current keeper bounty", async () => {
+    await swapRouterProgram.methods
+      .setKeeperBounty(20)
+      .accounts({ routerState, authority: user })
+      .rpc();
+
+    const expiry = inOneHour();
+    await placeOrder(1, 50_000_000, 10_000_000, expiry);
+
+    const { order, inputEscrow } = orderAccounts(1);
+    const orderAccount = await swapRouterProgram.account.limitOrder.fetch(order);
+    assert.ok(orderAccount.owner.equals(user));
+    assert.ok(orderAccount.destination.equals(userDestination));
+    assert.equal(orderAccount.inputAmount.toNumber(), 50_000_000);
+    assert.equal(orderAccount.minOutputAmount.toNumber(), 10_000_000);
+    assert.equal(orderAccount.expiryUnixTs.toNumber(), expiry);
+    assert.equal(orderAccount.keeperBountyBps, 20);
+    assert.equal((await getAccount(provider.connection, inputEscrow)).amount.toString(), "50000000");
+    console.log("✅ Order placed");
+  });
+
+  it("Rejects an order that is already expired", async () => {
+    try {
+      await placeOrder(2, 1_000_000, 1, Math.floor(Date.now() / 1000) - 60);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Order expired");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects an exact-out payload on fill", async () => {
+    const keeper = anchor.web3.Keypair.generate();
+    const keeperToken = await createAccount(provider.connection, payer, outputMint, keeper.publicKey);
+    const { inputEscrow, outputEscrow } = orderAccounts(1);
+
+    const tail = Buffer.alloc(19);
+    tail.writeBigUInt64LE(BigInt(50_000_000), 0);
+    tail.writeBigUInt64LE(BigInt(20_000_000), 8);
+    tail.writeUInt16LE(50, 16);
+    const payload = Buffer.concat([EXACT_OUT_ROUTE_DISCRIMINATOR, Buffer.from([0, 0, 0, 0]), tail]);
+
+    try {
+      await swapRouterProgram.methods
+        .fillLimitOrder(payload)
+        .accounts({
+          routerState,
+          ...orderAccounts(1),
+          owner: user,
+          ownerDestinationToken: userDestination,
+          feeTreasury: pda([Buffer.from("fee_treasury"), outputMint.toBuffer()]),
+          keeper: keeper.publicKey,
+          keeperToken,
+          jupiterProgram: JUPITER_PROGRAM_ID,
+        })
+        .remainingAccounts(
+          [inputEscrow, outputEscrow].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+        )
+        .signers([keeper])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Unsupported Jupiter instruction");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Cancels a limit order and refunds the escrow", async () => {
+    const { order, inputEscrow, outputEscrow } = orderAccounts(1);
+    const before = BigInt((await getAccount(provider.connection, userSource)).amount);
+
+    await swapRouterProgram.methods
+      .cancelLimitOrder()
+      .accounts({
+        ...orderAccounts(1),
+        ownerSourceToken: userSource,
+        ownerDestinationToken: userDestination,
+        owner: user,
+      })
+      .rpc();
+
+    const after = BigInt((await getAccount(provider.connection, userSource)).amount);
+    assert.equal((after - before).toString(), "50000000");
+    for (const closed of [order, inputEscrow, outputEscrow]) {
+      assert.isNull(await provider.connection.getAccountInfo(closed));
+    }
+    console.log("✅ Order cancelled and refunded");
+  });
+
+  // Escrows of transfer-fee mints hold withheld fees, which are harvested
+  // to the mint before the escrows are closed
+  describe("Transfer-fee mints", () => {
+    const FEE_BPS = 100;
+    const AMOUNT = BigInt(10_000_000);
+    const QUOTED_OUT = BigInt(20_000_000);
+    const keeper = anchor.web3.Keypair.generate();
+
+    let feeInputMint: anchor.web3.PublicKey;
+    let feeOutputMint: anchor.web3.PublicKey;
+    let ownerSource: anchor.web3.PublicKey;
+    let ownerDestination: anchor.web3.PublicKey;
+    let keeperToken: anchor.web3.PublicKey;
+    let pool: MockPool;
+
+    const feeOrderAccounts = (orderId: number) => ({
+      ...orderAccounts(orderId),
+      inputMint: feeInputMint,
+      outputMint: feeOutputMint,
+      inputTokenProgram: TOKEN_2022_PROGRAM_ID,
+      outputTokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+
+    const balance = async (account: anchor.web3.PublicKey) =>
+      (await getAccount(provider.connection, account, undefined, TOKEN_2022_PROGRAM_ID)).amount;
+
+    const placeFeeOrder = async (orderId: number) => {
+      await swapRouterProgram.methods
+        .placeLimitOrder(
+          new anchor.BN(orderId),
+          new anchor.BN(AMOUNT.toString()),
+          new anchor.BN(1),
+          new anchor.BN(inOneHour())
+        )
+        .accounts({
+          routerState,
+          ...feeOrderAccounts(orderId),
+          ownerSourceToken: ownerSource,
+          ownerDestinationToken: ownerDestination,
+          owner: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      const { order } = feeOrderAccounts(orderId);
+      return BigInt((await swapRouterProgram.account.limitOrder.fetch(order)).inputAmount.toString());
+    };
+
+    const fillFeeOrder = (orderId: number, payload: Buffer) => {
+      const { order, inputEscrow, outputEscrow } = feeOrderAccounts(orderId);
+      return swapRouterProgram.methods
+        .fillLimitOrder(payload)
+        .accounts({
+          routerState,
+          ...feeOrderAccounts(orderId),
+          owner: user,
+          ownerDestinationToken: ownerDestination,
+          feeTreasury: pda([Buffer.from("fee_treasury"), feeOutputMint.toBuffer()]),
+          keeper: keeper.publicKey,
+          keeperToken,
+          jupiterProgram: JUPITER_PROGRAM_ID,
+        })
+        .remainingAccounts(mockRouteAccounts(pool, order, inputEscrow, outputEscrow))
+        .signers([keeper])
+        .rpc();
+    };
+
+    const assertClosed = async (orderId: number) => {
+      const { order, inputEscrow, outputEscrow } = feeOrderAccounts(orderId);
+      for (const closed of [order, inputEscrow, outputEscrow]) {
+        assert.isNull(await provider.connection.getAccountInfo(closed));
+      }
+    };
+
+    before(async () => {
+      feeInputMint = await createTransferFeeMint(provider, FEE_BPS);
+      feeOutputMint = await createTransferFeeMint(provider, FEE_BPS);
+      ownerSource = await createAccount(
+        provider.connection, payer, feeInputMint, user, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      ownerDestination = await createAccount(
+        provider.connection, payer, feeOutputMint, user, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      keeperToken = await createAccount(
+        provider.connection, payer, feeOutputMint, keeper.publicKey, undefined, undefined, TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        provider.connection, payer, feeInputMint, ownerSource, user, BigInt(10) * AMOUNT, [], undefined, TOKEN_2022_PROGRAM_ID
+      );
+      pool = await createMockPool(
+        provider, feeInputMint, feeOutputMint, BigInt(10) * QUOTED_OUT, TOKEN_2022_PROGRAM_ID, TOKEN_2022_PROGRAM_ID
+      );
+
+      await swapRouterProgram.methods
+        .initializeFeeTreasury()
+        .accounts({
+          routerState,
+          feeTreasury: pda([Buffer.from("fee_treasury"), feeOutputMint.toBuffer()]),
+          mint: feeOutputMint,
+          payer: user,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    });
+
+    it("Rejects a keeper payload charging a platform fee", async () => {
+      const escrowed = await placeFeeOrder(10);
+      try {
+        await fillFeeOrder(10, mockRoutePayload(escrowed, QUOTED_OUT, 50, 10));
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        assert.include(error.error.errorMessage, "Jupiter platform fee not allowed");
+        console.log("✅ Correctly rejected");
+      }
+    });
+
+    it("Fills an order and closes escrows holding withheld fees", async () => {
+      const { order } = feeOrderAccounts(10);
+      const escrowed = BigInt((await swapRouterProgram.account.limitOrder.fetch(order)).inputAmount.toString());
+      assert.equal(escrowed, AMOUNT - transferFeeOf(AMOUNT, FEE_BPS));
+      const before = await balance(ownerDestination);
+
+      await fillFeeOrder(10, mockRoutePayload(escrowed, QUOTED_OUT));
+
+      assert.ok((await balance(ownerDestination)) > before);
+      await assertClosed(10);
+      console.log("✅ Order filled, escrows closed");
+    });
+
+    it("Cancels an order whose escrow holds withheld fees", async () => {
+      const escrowed = await placeFeeOrder(11);
+      const before = await balance(ownerSource);
+
+      await swapRouterProgram.methods
+        .cancelLimitOrder()
+        .accounts({
+          ...feeOrderAccounts(11),
+          ownerSourceToken: ownerSource,
+          ownerDestinationToken: ownerDestination,
+          owner: user,
+        })
+        .rpc();
+
+      const refunded = (await balance(ownerSource)) - before;
+      assert.equal(refunded, escrowed - transferFeeOf(escrowed, FEE_BPS));
+      await assertClosed(11);
+      console.log("✅ Order cancelled, escrows closed");
+    });
+  });
+
+  after(async () => {
+    await swapRouterProgram.methods
+      .setKeeperBounty(0)
+      .accounts({ routerState, authority: user })
+      .rpc();
+  });
+});
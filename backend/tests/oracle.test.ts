@@ -0,0 +1,308 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SwapRouter } from "../target/types/swap_router";
+import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("Oracle Guard Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+
+  let routerState: anchor.web3.PublicKey;
+  let mintA: anchor.web3.PublicKey;
+  let mintB: anchor.web3.PublicKey;
+  let pool: anchor.web3.PublicKey;
+  let vaultA: anchor.web3.PublicKey;
+  let vaultB: anchor.web3.PublicKey;
+  let userTokenA: anchor.web3.PublicKey;
+  let userTokenB: anchor.web3.PublicKey;
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, swapRouterProgram.programId)[0];
+  const pushOracle = (mint: anchor.web3.PublicKey) => pda([Buffer.from("push_oracle"), mint.toBuffer()]);
+  const priceFeed = (mint: anchor.web3.PublicKey) => pda([Buffer.from("price_feed"), mint.toBuffer()]);
+
+  const pushPrice = (mint: anchor.web3.PublicKey, price: number) =>
+    swapRouterProgram.methods
+      .pushPrice(new anchor.BN(price), -2)
+      .accounts({ pushOracle: pushOracle(mint), pusher: user })
+      .rpc();
+
+  // Pool swap A -> B passing the price feeds of both mints
+  const guardedSwap = (amountIn: number) =>
+    swapRouterProgram.methods
+      .swap(new anchor.BN(amountIn), new anchor.BN(1))
+      .accounts({
+        swap: {
+          routerState,
+          user,
+          userSourceToken: userTokenA,
+          userDestinationToken: userTokenB,
+          outputMint: mintB,
+          feeTreasury: pda([Buffer.from("fee_treasury"), mintB.toBuffer()]),
+          inputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+          outputMintStats: pda([Buffer.from("mint_stats"), mintB.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          inputPriceFeed: priceFeed(mintA),
+          inputOracle: pushOracle(mintA),
+          outputPriceFeed: priceFeed(mintB),
+          outputOracle: pushOracle(mintB),
+        },
+        pool,
+        vaultA,
+        vaultB,
+        mintA,
+        mintB,
+      })
+      .rpc();
+
+  before(async () => {
+    routerState = pda([Buffer.from("router_state")]);
+
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+
+    const m1 = await createMint(provider.connection, payer, user, null, 6);
+    const m2 = await createMint(provider.connection, payer, user, null, 6);
+    [mintA, mintB] = Buffer.compare(m1.toBuffer(), m2.toBuffer()) < 0 ? [m1, m2] : [m2, m1];
+
+    pool = pda([Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer()]);
+    vaultA = pda([Buffer.from("pool_vault"), pool.toBuffer(), mintA.toBuffer()]);
+    vaultB = pda([Buffer.from("pool_vault"), pool.toBuffer(), mintB.toBuffer()]);
+    const lpMint = pda([Buffer.from("pool_lp"), pool.toBuffer()]);
+
+    userTokenA = await createAccount(provider.connection, payer, mintA, user);
+    userTokenB = await createAccount(provider.connection, payer, mintB, user);
+    await mintTo(provider.connection, payer, mintA, userTokenA, user, 1_000_000_000);
+    await mintTo(provider.connection, payer, mintB, userTokenB, user, 1_000_000_000);
+
+    for (const mint of [mintA, mintB]) {
+      await swapRouterProgram.methods
+        .initializeFeeTreasury()
+        .accounts({
+          routerState,
+          feeTreasury: pda([Buffer.from("fee_treasury"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      await swapRouterProgram.methods
+        .initializeMintStats()
+        .accounts({
+          mintStats: pda([Buffer.from("mint_stats"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    await swapRouterProgram.methods
+      .createPool()
+      .accounts({
+        pool,
+        mintA,
+        mintB,
+        vaultA,
+        vaultB,
+        lpMint,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    // Pool price: 1 A = 4 B
+    const userLpToken = await createAccount(provider.connection, payer, lpMint, user);
+    await swapRouterProgram.methods
+      .addLiquidity(new anchor.BN(100_000_000), new anchor.BN(400_000_000), new anchor.BN(0))
+      .accounts({
+        pool,
+        mintA,
+        mintB,
+        vaultA,
+        vaultB,
+        lpMint,
+        user,
+        userTokenA,
+        userTokenB,
+        userLpToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // Push oracles quoting A at 4.00 and B at 1.00
+    for (const mint of [mintA, mintB]) {
+      await swapRouterProgram.methods
+        .initializePushOracle(user)
+        .accounts({
+          routerState,
+          pushOracle: pushOracle(mint),
+          mint,
+          authority: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      await swapRouterProgram.methods
+        .registerPriceFeed({ push: {} }, pushOracle(mint), Array(32).fill(0))
+        .accounts({
+          routerState,
+          priceFeed: priceFeed(mint),
+          mint,
+          authority: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+    await pushPrice(mintA, 400);
+    await pushPrice(mintB, 100);
+
+    await swapRouterProgram.methods
+      .setOracleGuard(100, 60)
+      .accounts({ routerState, authority: user })
+      .rpc();
+  });
+
+  it("Rejects a push feed that does not point at the mint's push oracle", async () => {
+    try {
+      await swapRouterProgram.methods
+        .updatePriceFeed({ push: {} }, pushOracle(mintB), Array(32).fill(0))
+        .accounts({ routerState, priceFeed: priceFeed(mintA), authority: user })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid oracle configuration");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Only the pusher can push prices", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    try {
+      await swapRouterProgram.methods
+        .pushPrice(new anchor.BN(1), -2)
+        .accounts({ pushOracle: pushOracle(mintA), pusher: stranger.publicKey })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Unauthorized");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Allows a swap priced in line with the oracles", async () => {
+    await guardedSwap(100_000);
+    console.log("✅ Swap within the deviation threshold");
+  });
+
+  it("Rejects a swap deviating from the oracle price", async () => {
+    // Oracles now say 1 A = 2 B while the pool still pays ~4 B
+    await pushPrice(mintB, 200);
+
+    try {
+      await guardedSwap(100_000);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "deviates too far from the oracle price");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a swap passing only one side's feed", async () => {
+    try {
+      await swapRouterProgram.methods
+        .swap(new anchor.BN(100_000), new anchor.BN(1))
+        .accounts({
+          swap: {
+            routerState,
+            user,
+            userSourceToken: userTokenA,
+            userDestinationToken: userTokenB,
+            outputMint: mintB,
+            feeTreasury: pda([Buffer.from("fee_treasury"), mintB.toBuffer()]),
+            inputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+            outputMintStats: pda([Buffer.from("mint_stats"), mintB.toBuffer()]),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            inputPriceFeed: priceFeed(mintA),
+            inputOracle: pushOracle(mintA),
+          },
+          pool,
+          vaultA,
+          vaultB,
+          mintA,
+          mintB,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Price feed accounts are required");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Rejects a guarded swap that leaves out feeds or oracles", async () => {
+    const swapPassing = (oracleAccounts: object) =>
+      swapRouterProgram.methods
+        .swap(new anchor.BN(100_000), new anchor.BN(1))
+        .accounts({
+          swap: {
+            routerState,
+            user,
+            userSourceToken: userTokenA,
+            userDestinationToken: userTokenB,
+            outputMint: mintB,
+            feeTreasury: pda([Buffer.from("fee_treasury"), mintB.toBuffer()]),
+            inputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+            outputMintStats: pda([Buffer.from("mint_stats"), mintB.toBuffer()]),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            ...oracleAccounts,
+          },
+          pool,
+          vaultA,
+          vaultB,
+          mintA,
+          mintB,
+        })
+        .rpc();
+
+    const cases: [object, string][] = [
+      // Both mints have feeds, so skipping everything no longer bypasses the guard
+      [{}, "Price feed accounts are required"],
+      [{ inputPriceFeed: priceFeed(mintA), outputPriceFeed: priceFeed(mintB) }, "Oracle account does not match"],
+    ];
+    for (const [oracleAccounts, message] of cases) {
+      try {
+        await swapPassing(oracleAccounts);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        assert.include(error.error.errorMessage, message);
+      }
+    }
+    console.log("✅ Guard cannot be skipped");
+  });
+
+  after(async () => {
+    await swapRouterProgram.methods
+      .setOracleGuard(0, 0)
+      .accounts({ routerState, authority: user })
+      .rpc();
+  });
+});
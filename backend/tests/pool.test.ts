@@ -0,0 +1,562 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SwapRouter } from "../target/types/swap_router";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("Native Pool Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+
+  let routerState: anchor.web3.PublicKey;
+  let mintA: anchor.web3.PublicKey;
+  let mintB: anchor.web3.PublicKey;
+  let pool: anchor.web3.PublicKey;
+  let vaultA: anchor.web3.PublicKey;
+  let vaultB: anchor.web3.PublicKey;
+  let lpMint: anchor.web3.PublicKey;
+  let userTokenA: anchor.web3.PublicKey;
+  let userTokenB: anchor.web3.PublicKey;
+  let userLpToken: anchor.web3.PublicKey;
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, swapRouterProgram.programId)[0];
+
+  before(async () => {
+    console.log("🔧 Setting up pool test environment...");
+
+    routerState = pda([Buffer.from("router_state")]);
+
+    // Router and DEX registry may already exist from other suites
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+    try {
+      await swapRouterProgram.methods
+        .initializeDexRegistry()
+        .accounts({
+          routerState,
+          dexRegistry: pda([Buffer.from("dex_registry")]),
+          authority: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  DEX registry already initialized");
+    }
+
+    // Pools require mint_a < mint_b
+    const m1 = await createMint(provider.connection, payer, user, null, 6);
+    const m2 = await createMint(provider.connection, payer, user, null, 6);
+    [mintA, mintB] = Buffer.compare(m1.toBuffer(), m2.toBuffer()) < 0 ? [m1, m2] : [m2, m1];
+
+    pool = pda([Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer()]);
+    vaultA = pda([Buffer.from("pool_vault"), pool.toBuffer(), mintA.toBuffer()]);
+    vaultB = pda([Buffer.from("pool_vault"), pool.toBuffer(), mintB.toBuffer()]);
+    lpMint = pda([Buffer.from("pool_lp"), pool.toBuffer()]);
+
+    userTokenA = await createAccount(provider.connection, payer, mintA, user);
+    userTokenB = await createAccount(provider.connection, payer, mintB, user);
+    await mintTo(provider.connection, payer, mintA, userTokenA, user, 1_000_000_000);
+    await mintTo(provider.connection, payer, mintB, userTokenB, user, 1_000_000_000);
+
+    // Fee treasuries and stats for both sides of the pair
+    for (const mint of [mintA, mintB]) {
+      await swapRouterProgram.methods
+        .initializeFeeTreasury()
+        .accounts({
+          routerState,
+          feeTreasury: pda([Buffer.from("fee_treasury"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      await swapRouterProgram.methods
+        .initializeMintStats()
+        .accounts({
+          mintStats: pda([Buffer.from("mint_stats"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    console.log(" Setup complete!\n");
+  });
+
+  const liquidityAccounts = () => ({
+    pool,
+    mintA,
+    mintB,
+    vaultA,
+    vaultB,
+    lpMint,
+    user,
+    userTokenA,
+    userTokenB,
+    userLpToken,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  it("Creates a pool", async () => {
+    await swapRouterProgram.methods
+      .createPool()
+      .accounts({
+        pool,
+        mintA,
+        mintB,
+        vaultA,
+        vaultB,
+        lpMint,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgramA: TOKEN_PROGRAM_ID,
+        tokenProgramB: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    userLpToken = await createAccount(provider.connection, payer, lpMint, user);
+
+    const poolAccount = await swapRouterProgram.account.pool.fetch(pool);
+    assert.ok(poolAccount.mintA.equals(mintA));
+    assert.ok(poolAccount.mintB.equals(mintB));
+    assert.equal(poolAccount.lpSupply.toNumber(), 0);
+    console.log("✅ Pool created");
+  });
+
+  it("Rejects a pool with unordered mints", async () => {
+    const reversedPool = pda([Buffer.from("pool"), mintB.toBuffer(), mintA.toBuffer()]);
+
+    try {
+      await swapRouterProgram.methods
+        .createPool()
+        .accounts({
+          pool: reversedPool,
+          mintA: mintB,
+          mintB: mintA,
+          vaultA: pda([Buffer.from("pool_vault"), reversedPool.toBuffer(), mintB.toBuffer()]),
+          vaultB: pda([Buffer.from("pool_vault"), reversedPool.toBuffer(), mintA.toBuffer()]),
+          lpMint: pda([Buffer.from("pool_lp"), reversedPool.toBuffer()]),
+          payer: user,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid token pair");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Adds initial liquidity", async () => {
+    await swapRouterProgram.methods
+      .addLiquidity(new anchor.BN(100_000_000), new anchor.BN(400_000_000), new anchor.BN(0))
+      .accounts(liquidityAccounts())
+      .rpc();
+
+    // sqrt(100e6 * 400e6) = 200e6, minus the locked minimum
+    const lp = await getAccount(provider.connection, userLpToken);
+    assert.equal(lp.amount.toString(), (200_000_000 - 1_000).toString());
+
+    const poolAccount = await swapRouterProgram.account.pool.fetch(pool);
+    assert.equal(poolAccount.lpSupply.toNumber(), 200_000_000);
+    console.log("✅ Initial liquidity added");
+  });
+
+  it("Swaps A for B along the curve and charges the router fee", async () => {
+    const amountIn = 10_000_000;
+    const reserveA = BigInt((await getAccount(provider.connection, vaultA)).amount);
+    const reserveB = BigInt((await getAccount(provider.connection, vaultB)).amount);
+    // 0.25% LP fee on the input
+    const amountInWithFee = BigInt(amountIn) * BigInt(9_975);
+    const expectedOut = (reserveB * amountInWithFee) / (reserveA * BigInt(10_000) + amountInWithFee);
+
+    const feeTreasuryB = pda([Buffer.from("fee_treasury"), mintB.toBuffer()]);
+    const treasuryBefore = BigInt((await getAccount(provider.connection, feeTreasuryB)).amount);
+    const userBBefore = BigInt((await getAccount(provider.connection, userTokenB)).amount);
+
+    await swapRouterProgram.methods
+      .swap(new anchor.BN(amountIn), new anchor.BN(1))
+      .accounts({
+        swap: {
+          routerState,
+          user,
+          userSourceToken: userTokenA,
+          userDestinationToken: userTokenB,
+          outputMint: mintB,
+          feeTreasury: feeTreasuryB,
+          inputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+          outputMintStats: pda([Buffer.from("mint_stats"), mintB.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        pool,
+        vaultA,
+        vaultB,
+        mintA,
+        mintB,
+      })
+      .rpc();
+
+    const treasuryAfter = BigInt((await getAccount(provider.connection, feeTreasuryB)).amount);
+    const userBAfter = BigInt((await getAccount(provider.connection, userTokenB)).amount);
+    const fee = treasuryAfter - treasuryBefore;
+
+    assert.equal((userBAfter - userBBefore + fee).toString(), expectedOut.toString());
+
+    const router = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.equal(fee.toString(), ((expectedOut * BigInt(router.feeRateBps)) / BigInt(10000)).toString());
+
+    // The LP fee stays in the pool, so k grows
+    const reserveAAfter = BigInt((await getAccount(provider.connection, vaultA)).amount);
+    const reserveBAfter = BigInt((await getAccount(provider.connection, vaultB)).amount);
+    assert.equal(reserveAAfter.toString(), (reserveA + BigInt(amountIn)).toString());
+    assert.ok(reserveAAfter * reserveBAfter > reserveA * reserveB);
+    console.log("✅ Swap output:", expectedOut.toString(), "fee:", fee.toString());
+  });
+
+  it("Emits SwapExecuted for a pool swap", async () => {
+    const sig = await swapRouterProgram.methods
+      .swap(new anchor.BN(1_000_000), new anchor.BN(1))
+      .accounts({
+        swap: {
+          routerState,
+          user,
+          userSourceToken: userTokenB,
+          userDestinationToken: userTokenA,
+          outputMint: mintA,
+          feeTreasury: pda([Buffer.from("fee_treasury"), mintA.toBuffer()]),
+          inputMintStats: pda([Buffer.from("mint_stats"), mintB.toBuffer()]),
+          outputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        pool,
+        vaultA,
+        vaultB,
+        mintA,
+        mintB,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await provider.connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(swapRouterProgram.programId, swapRouterProgram.coder);
+    const events = [...parser.parseLogs(tx.meta.logMessages)];
+    const executed = events.find((e) => e.name === "swapExecuted");
+
+    assert.ok(executed, "SwapExecuted not emitted");
+    assert.ok(executed.data.user.equals(user));
+    assert.ok(executed.data.inputMint.equals(mintB));
+    assert.ok(executed.data.outputMint.equals(mintA));
+    assert.equal(executed.data.amountIn.toNumber(), 1_000_000);
+    assert.deepEqual(executed.data.routeKind, { nativePool: {} });
+    assert.equal(executed.data.slot.toNumber(), tx.slot);
+    console.log("✅ SwapExecuted:", executed.data.amountOut.toString(), "out");
+  });
+
+
+    try {
+      await swapRouterProgram.methods
+        .swap(new anchor.BN(1_000_000), new anchor.BN(1_000_000_000))
+        .accounts({
+          swap: {
+            routerState,
+            user,
+            userSourceToken: userTokenA,
+            userDestinationToken: userTokenB,
+            outputMint: mintB,
+            feeTreasury: pda([Buffer.from("fee_treasury"), mintB.toBuffer()]),
+            inputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+            outputMintStats: pda([Buffer.from("mint_stats"), mintB.toBuffer()]),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          },
+          pool,
+          vaultA,
+          vaultB,
+          mintA,
+          mintB,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Insufficient output amount");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Executes a two-hop route A -> B -> A through the pool", async () => {
+    const amountIn = 5_000_000;
+    const feeTreasuryA = pda([Buffer.from("fee_treasury"), mintA.toBuffer()]);
+    const treasuryBefore = BigInt((await getAccount(provider.connection, feeTreasuryA)).amount);
+    const userABefore = BigInt((await getAccount(provider.connection, userTokenA)).amount);
+    const userBBefore = BigInt((await getAccount(provider.connection, userTokenB)).amount);
+
+    // remaining_accounts: [intermediate B account, pool, vault_a, vault_b, mint_a, mint_b]
+    const poolHop = (inputMint: anchor.web3.PublicKey, outputMint: anchor.web3.PublicKey, amount: any) => ({
+      adapter: { nativePool: {} },
+      accountsStart: 1,
+      accountsLen: 5,
+      inputMint,
+      outputMint,
+      amount,
+      data: Buffer.alloc(0),
+    });
+
+    await swapRouterProgram.methods
+      .executeRoute(
+        [
+          poolHop(mintA, mintB, { exact: { 0: new anchor.BN(amountIn) } }),
+          poolHop(mintB, mintA, { allOfPrevious: {} }),
+        ],
+        null
+      )
+      .accounts({
+        swap: {
+          routerState,
+          user,
+          userSourceToken: userTokenA,
+          userDestinationToken: userTokenA,
+          outputMint: mintA,
+          feeTreasury: feeTreasuryA,
+          inputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+          outputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        dexRegistry: pda([Buffer.from("dex_registry")]),
+      })
+      .remainingAccounts([
+        { pubkey: userTokenB, isSigner: false, isWritable: true },
+        { pubkey: pool, isSigner: false, isWritable: false },
+        { pubkey: vaultA, isSigner: false, isWritable: true },
+        { pubkey: vaultB, isSigner: false, isWritable: true },
+        { pubkey: mintA, isSigner: false, isWritable: false },
+        { pubkey: mintB, isSigner: false, isWritable: false },
+      ])
+      .rpc();
+
+    const treasuryAfter = BigInt((await getAccount(provider.connection, feeTreasuryA)).amount);
+    const userAAfter = BigInt((await getAccount(provider.connection, userTokenA)).amount);
+    const userBAfter = BigInt((await getAccount(provider.connection, userTokenB)).amount);
+
+    // The intermediate leg is fully consumed and the round trip loses to the curve
+    assert.equal(userBAfter.toString(), userBBefore.toString());
+    assert.ok(treasuryAfter > treasuryBefore);
+    assert.ok(userAAfter < userABefore);
+    console.log("✅ Route fee:", (treasuryAfter - treasuryBefore).toString());
+  });
+
+  it("Splits the router fee with a registered partner", async () => {
+    const partnerAuthority = anchor.web3.Keypair.generate();
+    const partner = pda([Buffer.from("partner"), partnerAuthority.publicKey.toBuffer()]);
+    const partnerFeeAccount = pda([Buffer.from("partner_fees"), partner.toBuffer(), mintB.toBuffer()]);
+    const feeTreasuryB = pda([Buffer.from("fee_treasury"), mintB.toBuffer()]);
+
+    await swapRouterProgram.methods
+      .registerPartner(partnerAuthority.publicKey, 4_000)
+      .accounts({ routerState, partner, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+      .rpc();
+    await swapRouterProgram.methods
+      .initializePartnerFeeAccount()
+      .accounts({
+        partner,
+        partnerFeeAccount,
+        mint: mintB,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const treasuryBefore = BigInt((await getAccount(provider.connection, feeTreasuryB)).amount);
+
+    await swapRouterProgram.methods
+      .swap(new anchor.BN(10_000_000), new anchor.BN(1))
+      .accounts({
+        swap: {
+          routerState,
+          user,
+          userSourceToken: userTokenA,
+          userDestinationToken: userTokenB,
+          outputMint: mintB,
+          feeTreasury: feeTreasuryB,
+          inputMintStats: pda([Buffer.from("mint_stats"), mintA.toBuffer()]),
+          outputMintStats: pda([Buffer.from("mint_stats"), mintB.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          partner,
+          partnerFeeAccount,
+        },
+        pool,
+        vaultA,
+        vaultB,
+        mintA,
+        mintB,
+      })
+      .rpc();
+
+    const treasuryFee = BigInt((await getAccount(provider.connection, feeTreasuryB)).amount) - treasuryBefore;
+    const partnerFee = BigInt((await getAccount(provider.connection, partnerFeeAccount)).amount);
+    const totalFee = treasuryFee + partnerFee;
+    assert.ok(totalFee > BigInt(0));
+    assert.equal(partnerFee.toString(), ((totalFee * BigInt(4_000)) / BigInt(10_000)).toString());
+
+    const partnerAccount = await swapRouterProgram.account.partner.fetch(partner);
+    assert.equal(partnerAccount.swapCount.toNumber(), 1);
+    assert.equal(partnerAccount.totalFeesAccrued.toString(), partnerFee.toString());
+
+    // Only the partner authority can claim
+    const claimAccounts = {
+      partner,
+      authority: partnerAuthority.publicKey,
+      partnerFeeAccount,
+      mint: mintB,
+      destination: userTokenB,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    try {
+      await swapRouterProgram.methods
+        .claimPartnerFees(new anchor.BN(partnerFee.toString()))
+        .accounts({ ...claimAccounts, authority: user })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.ok(error);
+    }
+
+    await swapRouterProgram.methods
+      .claimPartnerFees(new anchor.BN(partnerFee.toString()))
+      .accounts(claimAccounts)
+      .signers([partnerAuthority])
+      .rpc();
+
+    assert.equal((await getAccount(provider.connection, partnerFeeAccount)).amount.toString(), "0");
+    const claimed = await swapRouterProgram.account.partner.fetch(partner);
+    assert.equal(claimed.totalFeesClaimed.toString(), partnerFee.toString());
+    console.log("✅ Partner fee:", partnerFee.toString(), "treasury fee:", treasuryFee.toString());
+  });
+
+  it("Applies volume fee tiers to users with swap stats", async () => {
+    const userStats = pda([Buffer.from("user_stats"), user.toBuffer()]);
+    const feeTreasuryB = pda([Buffer.from("fee_treasury"), mintB.toBuffer()]);
+
+    await swapRouterProgram.methods
+      .initializeUserStats()
+      .accounts({ userStats, user, systemProgram: anchor.web3.SystemProgram.programId })
+      .rpc();
+    // Any volume at all unlocks a zero fee; only B counts as volume
+    await swapRouterProgram.methods
+      .setFeeTiers([{ minVolume: new anchor.BN(1), feeRateBps: 0 }])
+      .accounts({ routerState, authority: user })
+      .rpc();
+    await swapRouterProgram.methods
+      .registerVolumeMint(mintB)
+      .accounts({ routerState, authority: user })
+      .rpc();
+
+    const swapWithStats = (toB: boolean) =>
+      swapRouterProgram.methods
+        .swap(new anchor.BN(1_000_000), new anchor.BN(1))
+        .accounts({
+          swap: {
+            routerState,
+            user,
+            userSourceToken: toB ? userTokenA : userTokenB,
+            userDestinationToken: toB ? userTokenB : userTokenA,
+            outputMint: toB ? mintB : mintA,
+            feeTreasury: pda([Buffer.from("fee_treasury"), (toB ? mintB : mintA).toBuffer()]),
+            inputMintStats: pda([Buffer.from("mint_stats"), (toB ? mintA : mintB).toBuffer()]),
+            outputMintStats: pda([Buffer.from("mint_stats"), (toB ? mintB : mintA).toBuffer()]),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            userStats,
+          },
+          pool,
+          vaultA,
+          vaultB,
+          mintA,
+          mintB,
+        })
+        .rpc();
+    const treasuryBalance = async () => BigInt((await getAccount(provider.connection, feeTreasuryB)).amount);
+    const userBBalance = async () => BigInt((await getAccount(provider.connection, userTokenB)).amount);
+
+    // Output in A is not volume: recorded, but the tier stays out of reach
+    await swapWithStats(false);
+    let stats = await swapRouterProgram.account.userSwapStats.fetch(userStats);
+    assert.equal(stats.swapCount.toNumber(), 1);
+    assert.equal(stats.totalVolume.toString(), "0");
+
+    // First swap into B pays the base fee and records its gross output
+    let before = await treasuryBalance();
+    const userBBefore = await userBBalance();
+    await swapWithStats(true);
+    const fee = (await treasuryBalance()) - before;
+    assert.ok(fee > BigInt(0));
+
+    stats = await swapRouterProgram.account.userSwapStats.fetch(userStats);
+    assert.equal(stats.swapCount.toNumber(), 2);
+    assert.equal(stats.totalVolume.toString(), ((await userBBalance()) - userBBefore + fee).toString());
+
+    // Second swap falls into the discounted tier
+    before = await treasuryBalance();
+    await swapWithStats(true);
+    assert.equal((await treasuryBalance()).toString(), before.toString());
+
+    await swapRouterProgram.methods
+      .setFeeTiers([])
+      .accounts({ routerState, authority: user })
+      .rpc();
+    await swapRouterProgram.methods
+      .unregisterVolumeMint(mintB)
+      .accounts({ routerState, authority: user })
+      .rpc();
+    console.log("✅ Volume tier applied");
+  });
+
+  it("Removes liquidity proportionally", async () => {
+    const lp = await getAccount(provider.connection, userLpToken);
+    const lpAmount = lp.amount / BigInt(2);
+
+    const poolBefore = await swapRouterProgram.account.pool.fetch(pool);
+    const reserveA = BigInt((await getAccount(provider.connection, vaultA)).amount);
+    const userABefore = BigInt((await getAccount(provider.connection, userTokenA)).amount);
+
+    await swapRouterProgram.methods
+      .removeLiquidity(new anchor.BN(lpAmount.toString()), new anchor.BN(0), new anchor.BN(0))
+      .accounts(liquidityAccounts())
+      .rpc();
+
+    const userAAfter = BigInt((await getAccount(provider.connection, userTokenA)).amount);
+    const expectedA = (lpAmount * reserveA) / BigInt(poolBefore.lpSupply.toString());
+    assert.equal((userAAfter - userABefore).toString(), expectedA.toString());
+
+    const poolAfter = await swapRouterProgram.account.pool.fetch(pool);
+    assert.equal(
+      poolAfter.lpSupply.toString(),
+      (BigInt(poolBefore.lpSupply.toString()) - lpAmount).toString()
+    );
+    console.log("✅ Liquidity removed");
+  });
+});
@@ -4,7 +4,7 @@ dotenv.config();
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { SwapRouter } from "../target/types/swap_router";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, createMint, getAccount } from "@solana/spl-token";
 import { assert } from "chai";
 
 describe("Jupiter Swap Router Tests", () => {
@@ -61,6 +61,266 @@ describe("Jupiter Swap Router Tests", () => {
     }
   });
 
+  it("Can initialize fee treasury", async () => {
+    console.log("\n🏦 Test: Initialize Fee Treasury");
+
+    const mint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      provider.wallet.publicKey,
+      null,
+      6
+    );
+
+    const [feeTreasury] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_treasury"), mint.toBuffer()],
+      swapRouterProgram.programId
+    );
+
+    const tx = await swapRouterProgram.methods
+      .initializeFeeTreasury()
+      .accounts({
+        routerState: routerState,
+        feeTreasury: feeTreasury,
+        mint: mint,
+        payer: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log("Transaction signature:", tx);
+
+    // Treasury must be owned by the router PDA
+    const treasuryAccount = await getAccount(provider.connection, feeTreasury);
+    assert.ok(treasuryAccount.owner.equals(routerState));
+    assert.ok(treasuryAccount.mint.equals(mint));
+    assert.equal(treasuryAccount.amount.toString(), "0");
+    console.log("✅ Fee treasury owned by router state");
+  });
+
+  it("Can initialize mint stats", async () => {
+    console.log("\n📊 Test: Initialize Mint Stats");
+
+    const mint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      provider.wallet.publicKey,
+      null,
+      6
+    );
+
+    const [mintStats] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_stats"), mint.toBuffer()],
+      swapRouterProgram.programId
+    );
+
+    await swapRouterProgram.methods
+      .initializeMintStats()
+      .accounts({
+        mintStats: mintStats,
+        mint: mint,
+        payer: provider.wallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const stats = await swapRouterProgram.account.mintStats.fetch(mintStats);
+    assert.ok(stats.mint.equals(mint));
+    assert.equal(stats.swapCount.toNumber(), 0);
+    assert.equal(stats.volumeIn.toString(), "0");
+    assert.equal(stats.volumeOut.toString(), "0");
+    console.log("✅ Mint stats initialized");
+  });
+
+  it("Authority can update fee rate", async () => {
+    console.log("\n⚙️  Test: Set Fee Rate");
+
+    const before = await swapRouterProgram.account.routerState.fetch(routerState);
+
+    await swapRouterProgram.methods
+      .setFeeRate(50)
+      .accounts({ routerState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    let after = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.equal(after.feeRateBps, 50);
+
+    // Restore original fee
+    await swapRouterProgram.methods
+      .setFeeRate(before.feeRateBps)
+      .accounts({ routerState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    after = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.equal(after.feeRateBps, before.feeRateBps);
+    console.log("✅ Fee rate updated and restored");
+  });
+
+  it("Rejects admin calls from non-authority", async () => {
+    console.log("\n🔒 Test: Non-authority cannot set fee");
+
+    const intruder = anchor.web3.Keypair.generate();
+
+    try {
+      await swapRouterProgram.methods
+        .setFeeRate(100)
+        .accounts({ routerState, authority: intruder.publicKey })
+        .signers([intruder])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Unauthorized");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Can pause and unpause the router", async () => {
+    console.log("\n⏸️  Test: Pause Switch");
+
+    await swapRouterProgram.methods
+      .setPaused(true)
+      .accounts({ routerState, authority: provider.wallet.publicKey })
+      .rpc();
+    let state = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.isTrue(state.paused);
+
+    await swapRouterProgram.methods
+      .setPaused(false)
+      .accounts({ routerState, authority: provider.wallet.publicKey })
+      .rpc();
+    state = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.isFalse(state.paused);
+    console.log("✅ Pause toggled");
+  });
+
+  it("Two-step authority transfer", async () => {
+    console.log("\n🔑 Test: Propose / Accept Authority");
+
+    const newAuthority = anchor.web3.Keypair.generate();
+
+    await swapRouterProgram.methods
+      .proposeAuthority(newAuthority.publicKey)
+      .accounts({ routerState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    let state = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.ok(state.pendingAuthority.equals(newAuthority.publicKey));
+    assert.ok(state.authority.equals(provider.wallet.publicKey));
+
+    await swapRouterProgram.methods
+      .acceptAuthority()
+      .accounts({ routerState, newAuthority: newAuthority.publicKey })
+      .signers([newAuthority])
+      .rpc();
+
+    state = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.ok(state.authority.equals(newAuthority.publicKey));
+
+    // Hand authority back to the provider wallet
+    await swapRouterProgram.methods
+      .proposeAuthority(provider.wallet.publicKey)
+      .accounts({ routerState, authority: newAuthority.publicKey })
+      .signers([newAuthority])
+      .rpc();
+    await swapRouterProgram.methods
+      .acceptAuthority()
+      .accounts({ routerState, newAuthority: provider.wallet.publicKey })
+      .rpc();
+
+    state = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.ok(state.authority.equals(provider.wallet.publicKey));
+    console.log("✅ Authority transferred and returned");
+  });
+
+  it("Can register and unregister a vault program", async () => {
+    console.log("\n🏛️  Test: Vault Program Allow-list");
+
+    const vaultProgramId = anchor.web3.Keypair.generate().publicKey;
+
+    await swapRouterProgram.methods
+      .registerVaultProgram(vaultProgramId)
+      .accounts({ routerState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    let state = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.ok(state.vaultPrograms.some((p) => p.equals(vaultProgramId)));
+
+    await swapRouterProgram.methods
+      .unregisterVaultProgram(vaultProgramId)
+      .accounts({ routerState, authority: provider.wallet.publicKey })
+      .rpc();
+
+    state = await swapRouterProgram.account.routerState.fetch(routerState);
+    assert.notOk(state.vaultPrograms.some((p) => p.equals(vaultProgramId)));
+    console.log("✅ Vault program allow-list updated");
+  });
+
+  it("Can manage the DEX registry", async () => {
+    console.log("\n📒 Test: DEX Registry");
+
+    const [dexRegistry] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("dex_registry")],
+      swapRouterProgram.programId
+    );
+
+    try {
+      await swapRouterProgram.methods
+        .initializeDexRegistry()
+        .accounts({
+          routerState,
+          dexRegistry,
+          authority: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  DEX registry already initialized:", error.message);
+    }
+
+    let registry = await swapRouterProgram.account.dexRegistry.fetch(dexRegistry);
+    assert.ok(registry.entries.some((e) => e.programId.equals(JUPITER_PROGRAM_ID)));
+
+    const raydiumAmm = new anchor.web3.PublicKey("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+    await swapRouterProgram.methods
+      .addDexProgram(raydiumAmm, { raydiumAmm: {} })
+      .accounts({ routerState, dexRegistry, authority: provider.wallet.publicKey })
+      .rpc();
+
+    registry = await swapRouterProgram.account.dexRegistry.fetch(dexRegistry);
+    const entry = registry.entries.find((e) => e.programId.equals(raydiumAmm));
+    assert.ok(entry);
+    assert.deepEqual(entry.kind, { raydiumAmm: {} });
+
+    await swapRouterProgram.methods
+      .removeDexProgram(raydiumAmm)
+      .accounts({ routerState, dexRegistry, authority: provider.wallet.publicKey })
+      .rpc();
+
+    registry = await swapRouterProgram.account.dexRegistry.fetch(dexRegistry);
+    assert.notOk(registry.entries.some((e) => e.programId.equals(raydiumAmm)));
+    console.log("✅ DEX registry updated");
+  });
+
+  it("Rejects registering a DEX as a native pool", async () => {
+    const [dexRegistry] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("dex_registry")],
+      swapRouterProgram.programId
+    );
+
+    try {
+      await swapRouterProgram.methods
+        .addDexProgram(anchor.web3.Keypair.generate().publicKey, { nativePool: {} })
+        .accounts({ routerState, dexRegistry, authority: provider.wallet.publicKey })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Invalid DEX program");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
   it("Can get Jupiter quote (mock)", async () => {
     console.log("\n💱 Test: Get Jupiter Quote");
     
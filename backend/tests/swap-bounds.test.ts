@@ -0,0 +1,143 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SwapRouter } from "../target/types/swap_router";
+import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { JUPITER_PROGRAM_ID, MockPool, createMockPool, mockRouteAccounts, mockRoutePayload } from "./utils/jupiter";
+
+// min_amount_out and max_amount_in are checked against the measured deltas
+// of a real swap through the mock Jupiter program
+describe("Swap Amount Bounds Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+  const AMOUNT_IN = 1_000_000;
+  const QUOTED_OUT = 2_000_000;
+
+  let routerState: anchor.web3.PublicKey;
+  let inputMint: anchor.web3.PublicKey;
+  let outputMint: anchor.web3.PublicKey;
+  let userSource: anchor.web3.PublicKey;
+  let userDestination: anchor.web3.PublicKey;
+  let pool: MockPool;
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, swapRouterProgram.programId)[0];
+
+  const balance = async (account: anchor.web3.PublicKey) =>
+    (await getAccount(provider.connection, account)).amount;
+
+  // What the user nets from QUOTED_OUT after the router fee
+  const expectedNetOut = async () => {
+    const { feeRateBps } = await swapRouterProgram.account.routerState.fetch(routerState);
+    return QUOTED_OUT - Math.floor((QUOTED_OUT * feeRateBps) / 10_000);
+  };
+
+  const swap = (minAmountOut: number | null, maxAmountIn: number | null) =>
+    swapRouterProgram.methods
+      .executeJupiterSwap(
+        mockRoutePayload(AMOUNT_IN, QUOTED_OUT),
+        minAmountOut === null ? null : new anchor.BN(minAmountOut),
+        maxAmountIn === null ? null : new anchor.BN(maxAmountIn),
+        null,
+        null
+      )
+      .accounts({
+        swap: {
+          routerState,
+          user,
+          userSourceToken: userSource,
+          userDestinationToken: userDestination,
+          outputMint,
+          feeTreasury: pda([Buffer.from("fee_treasury"), outputMint.toBuffer()]),
+          inputMintStats: pda([Buffer.from("mint_stats"), inputMint.toBuffer()]),
+          outputMintStats: pda([Buffer.from("mint_stats"), outputMint.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        jupiterProgram: JUPITER_PROGRAM_ID,
+      })
+      .remainingAccounts(mockRouteAccounts(pool, user, userSource, userDestination))
+      .rpc();
+
+  const expectRejected = async (action: Promise<unknown>, message: string) => {
+    try {
+      await action;
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, message);
+      console.log("✅ Correctly rejected:", error.error.errorMessage);
+    }
+  };
+
+  before(async () => {
+    routerState = pda([Buffer.from("router_state")]);
+
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+
+    inputMint = await createMint(provider.connection, payer, user, null, 6);
+    outputMint = await createMint(provider.connection, payer, user, null, 6);
+    userSource = await createAccount(provider.connection, payer, inputMint, user);
+    userDestination = await createAccount(provider.connection, payer, outputMint, user);
+    await mintTo(provider.connection, payer, inputMint, userSource, user, 100 * AMOUNT_IN);
+    pool = await createMockPool(provider, inputMint, outputMint, 100 * QUOTED_OUT);
+
+    await swapRouterProgram.methods
+      .initializeFeeTreasury()
+      .accounts({
+        routerState,
+        feeTreasury: pda([Buffer.from("fee_treasury"), outputMint.toBuffer()]),
+        mint: outputMint,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    for (const mint of [inputMint, outputMint]) {
+      await swapRouterProgram.methods
+        .initializeMintStats()
+        .accounts({
+          mintStats: pda([Buffer.from("mint_stats"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  it("Rejects a swap netting one unit less than min_amount_out", async () => {
+    await expectRejected(swap((await expectedNetOut()) + 1, null), "Insufficient output amount");
+  });
+
+  it("Accepts a swap netting exactly min_amount_out", async () => {
+    const netOut = await expectedNetOut();
+    const before = await balance(userDestination);
+    await swap(netOut, null);
+    assert.equal((await balance(userDestination)) - before, BigInt(netOut));
+    console.log("✅ Netted exactly min_amount_out:", netOut);
+  });
+
+  it("Rejects a swap spending one unit more than max_amount_in", async () => {
+    await expectRejected(swap(null, AMOUNT_IN - 1), "Slippage tolerance exceeded");
+  });
+
+  it("Accepts a swap spending exactly max_amount_in", async () => {
+    const before = await balance(userSource);
+    await swap(null, AMOUNT_IN);
+    assert.equal(before - (await balance(userSource)), BigInt(AMOUNT_IN));
+    console.log("✅ Spent exactly max_amount_in:", AMOUNT_IN);
+  });
+});
@@ -0,0 +1,241 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SwapRouter } from "../target/types/swap_router";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  createAccount,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeTransferHookInstruction,
+  createMint,
+  getAccount,
+  getMintLen,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("Token-2022 Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+  const TRANSFER_FEE_BPS = 100;
+
+  let routerState: anchor.web3.PublicKey;
+  let feeMint: anchor.web3.PublicKey;
+  let plainMint: anchor.web3.PublicKey;
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, swapRouterProgram.programId)[0];
+
+  const programFor = (mint: anchor.web3.PublicKey) =>
+    mint.equals(feeMint) ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
+
+  const balance = async (account: anchor.web3.PublicKey, mint: anchor.web3.PublicKey) =>
+    BigInt((await getAccount(provider.connection, account, undefined, programFor(mint))).amount);
+
+  // Token-2022 mint with a single extension initialized before the mint itself
+  const createExtensionMint = async (
+    extension: ExtensionType,
+    initExtension: (mint: anchor.web3.PublicKey) => anchor.web3.TransactionInstruction
+  ) => {
+    const mint = anchor.web3.Keypair.generate();
+    const space = getMintLen([extension]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: user,
+        newAccountPubkey: mint.publicKey,
+        space,
+        lamports,
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      initExtension(mint.publicKey),
+      createInitializeMintInstruction(mint.publicKey, 6, user, null, TOKEN_2022_PROGRAM_ID)
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+  };
+
+  before(async () => {
+    routerState = pda([Buffer.from("router_state")]);
+
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority: user, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+
+    feeMint = await createExtensionMint(ExtensionType.TransferFeeConfig, (mint) =>
+      createInitializeTransferFeeConfigInstruction(
+        mint,
+        user,
+        user,
+        TRANSFER_FEE_BPS,
+        BigInt(1_000_000_000),
+        TOKEN_2022_PROGRAM_ID
+      )
+    );
+    plainMint = await createMint(provider.connection, payer, user, null, 6);
+
+    for (const mint of [feeMint, plainMint]) {
+      await swapRouterProgram.methods
+        .initializeFeeTreasury()
+        .accounts({
+          routerState,
+          feeTreasury: pda([Buffer.from("fee_treasury"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          tokenProgram: programFor(mint),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      await swapRouterProgram.methods
+        .initializeMintStats()
+        .accounts({
+          mintStats: pda([Buffer.from("mint_stats"), mint.toBuffer()]),
+          mint,
+          payer: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  it("Rejects a mint with a transfer hook", async () => {
+    const hookMint = await createExtensionMint(ExtensionType.TransferHook, (mint) =>
+      createInitializeTransferHookInstruction(
+        mint,
+        user,
+        anchor.web3.Keypair.generate().publicKey,
+        TOKEN_2022_PROGRAM_ID
+      )
+    );
+
+    try {
+      await swapRouterProgram.methods
+        .initializeMintStats()
+        .accounts({
+          mintStats: pda([Buffer.from("mint_stats"), hookMint.toBuffer()]),
+          mint: hookMint,
+          payer: user,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Token-2022 extension");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Accounts for the transfer fee in liquidity and swaps", async () => {
+    const [mintA, mintB] =
+      Buffer.compare(feeMint.toBuffer(), plainMint.toBuffer()) < 0 ? [feeMint, plainMint] : [plainMint, feeMint];
+    const pool = pda([Buffer.from("pool"), mintA.toBuffer(), mintB.toBuffer()]);
+    const vaultA = pda([Buffer.from("pool_vault"), pool.toBuffer(), mintA.toBuffer()]);
+    const vaultB = pda([Buffer.from("pool_vault"), pool.toBuffer(), mintB.toBuffer()]);
+    const lpMint = pda([Buffer.from("pool_lp"), pool.toBuffer()]);
+
+    const userToken = new Map<string, anchor.web3.PublicKey>();
+    for (const mint of [feeMint, plainMint]) {
+      const account = await createAccount(
+        provider.connection, payer, mint, user, undefined, undefined, programFor(mint)
+      );
+      await mintTo(
+        provider.connection, payer, mint, account, user, 1_000_000_000, [], undefined, programFor(mint)
+      );
+      userToken.set(mint.toBase58(), account);
+    }
+    const tokenOf = (mint: anchor.web3.PublicKey) => userToken.get(mint.toBase58())!;
+
+    await swapRouterProgram.methods
+      .createPool()
+      .accounts({
+        pool,
+        mintA,
+        mintB,
+        vaultA,
+        vaultB,
+        lpMint,
+        payer: user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgramA: programFor(mintA),
+        tokenProgramB: programFor(mintB),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const userLpToken = await createAccount(provider.connection, payer, lpMint, user);
+    const deposit = BigInt(100_000_000);
+    await swapRouterProgram.methods
+      .addLiquidity(new anchor.BN(deposit.toString()), new anchor.BN(deposit.toString()), new anchor.BN(0))
+      .accounts({
+        pool,
+        mintA,
+        mintB,
+        vaultA,
+        vaultB,
+        lpMint,
+        user,
+        userTokenA: tokenOf(mintA),
+        userTokenB: tokenOf(mintB),
+        userLpToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // The fee-mint vault holds the deposit minus the withheld fee
+    const feeVault = mintA.equals(feeMint) ? vaultA : vaultB;
+    const withheld = (deposit * BigInt(TRANSFER_FEE_BPS)) / BigInt(10_000);
+    assert.equal((await balance(feeVault, feeMint)).toString(), (deposit - withheld).toString());
+
+    // Swap plain -> fee mint: the router fee lands net of the transfer fee
+    const feeTreasury = pda([Buffer.from("fee_treasury"), feeMint.toBuffer()]);
+    const treasuryBefore = await balance(feeTreasury, feeMint);
+    const userOutBefore = await balance(tokenOf(feeMint), feeMint);
+
+    await swapRouterProgram.methods
+      .swap(new anchor.BN(1_000_000), new anchor.BN(1))
+      .accounts({
+        swap: {
+          routerState,
+          user,
+          userSourceToken: tokenOf(plainMint),
+          userDestinationToken: tokenOf(feeMint),
+          outputMint: feeMint,
+          feeTreasury,
+          inputMintStats: pda([Buffer.from("mint_stats"), plainMint.toBuffer()]),
+          outputMintStats: pda([Buffer.from("mint_stats"), feeMint.toBuffer()]),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        pool,
+        vaultA,
+        vaultB,
+        mintA,
+        mintB,
+      })
+      .rpc();
+
+    const treasuryFee = (await balance(feeTreasury, feeMint)) - treasuryBefore;
+    const userReceived = (await balance(tokenOf(feeMint), feeMint)) - userOutBefore;
+    assert.ok(treasuryFee > BigInt(0));
+    assert.ok(userReceived > BigInt(0));
+
+    const stats = await swapRouterProgram.account.mintStats.fetch(
+      pda([Buffer.from("mint_stats"), feeMint.toBuffer()])
+    );
+    assert.equal(stats.feesCollected.toString(), treasuryFee.toString());
+    console.log("✅ Treasury fee:", treasuryFee.toString(), "user received:", userReceived.toString());
+  });
+});
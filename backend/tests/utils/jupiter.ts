@@ -0,0 +1,88 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, createAccount, mintTo } from "@solana/spl-token";
+
+// The local validator loads programs/mock-jupiter at the Jupiter V6 address
+export const JUPITER_PROGRAM_ID = new anchor.web3.PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+export const ROUTE_DISCRIMINATOR = Buffer.from([229, 23, 203, 151, 122, 227, 173, 42]);
+
+export const [MOCK_POOL_AUTHORITY] = anchor.web3.PublicKey.findProgramAddressSync(
+  [Buffer.from("pool_authority")],
+  JUPITER_PROGRAM_ID
+);
+
+export type MockPool = {
+  sourceMint: anchor.web3.PublicKey;
+  destinationMint: anchor.web3.PublicKey;
+  poolSource: anchor.web3.PublicKey;
+  poolDestination: anchor.web3.PublicKey;
+  sourceTokenProgram: anchor.web3.PublicKey;
+  destinationTokenProgram: anchor.web3.PublicKey;
+};
+
+// route(route_plan, in_amount, quoted_out_amount, slippage_bps, platform_fee_bps)
+// with a single data-less route plan step; the mock pays exactly quotedOut
+export const mockRoutePayload = (
+  inAmount: number | bigint,
+  quotedOut: number | bigint,
+  slippageBps = 50,
+  platformFeeBps = 0
+) => {
+  const tail = Buffer.alloc(19);
+  tail.writeBigUInt64LE(BigInt(inAmount), 0);
+  tail.writeBigUInt64LE(BigInt(quotedOut), 8);
+  tail.writeUInt16LE(slippageBps, 16);
+  tail.writeUInt8(platformFeeBps, 18);
+  const plan = Buffer.concat([Buffer.from([1, 0, 0, 0]), Buffer.from([0, 100, 0, 1])]);
+  return Buffer.concat([ROUTE_DISCRIMINATOR, plan, tail]);
+};
+
+// Pool token accounts of the mock, with `liquidity` of the destination mint
+// (minted by the provider wallet, which must be its mint authority)
+export const createMockPool = async (
+  provider: anchor.AnchorProvider,
+  sourceMint: anchor.web3.PublicKey,
+  destinationMint: anchor.web3.PublicKey,
+  liquidity: number | bigint,
+  sourceTokenProgram = TOKEN_PROGRAM_ID,
+  destinationTokenProgram = TOKEN_PROGRAM_ID
+): Promise<MockPool> => {
+  const payer = provider.wallet.payer;
+  const poolSource = await createAccount(
+    provider.connection, payer, sourceMint, MOCK_POOL_AUTHORITY,
+    anchor.web3.Keypair.generate(), undefined, sourceTokenProgram
+  );
+  const poolDestination = await createAccount(
+    provider.connection, payer, destinationMint, MOCK_POOL_AUTHORITY,
+    anchor.web3.Keypair.generate(), undefined, destinationTokenProgram
+  );
+  await mintTo(
+    provider.connection, payer, destinationMint, poolDestination, payer, liquidity,
+    [], undefined, destinationTokenProgram
+  );
+  return { sourceMint, destinationMint, poolSource, poolDestination, sourceTokenProgram, destinationTokenProgram };
+};
+
+// Remaining accounts of a mock `route` from `source` to `destination`,
+// spent by `authority`: Jupiter's layout, then the pool
+export const mockRouteAccounts = (
+  pool: MockPool,
+  authority: anchor.web3.PublicKey,
+  source: anchor.web3.PublicKey,
+  destination: anchor.web3.PublicKey
+) =>
+  [
+    { pubkey: pool.sourceTokenProgram, isWritable: false },
+    { pubkey: authority, isWritable: false },
+    { pubkey: source, isWritable: true },
+    { pubkey: destination, isWritable: true },
+    { pubkey: JUPITER_PROGRAM_ID, isWritable: false },
+    { pubkey: pool.destinationMint, isWritable: false },
+    { pubkey: JUPITER_PROGRAM_ID, isWritable: false },
+    { pubkey: JUPITER_PROGRAM_ID, isWritable: false },
+    { pubkey: JUPITER_PROGRAM_ID, isWritable: false },
+    { pubkey: pool.sourceMint, isWritable: false },
+    { pubkey: pool.poolSource, isWritable: true },
+    { pubkey: pool.poolDestination, isWritable: true },
+    { pubkey: MOCK_POOL_AUTHORITY, isWritable: false },
+    { pubkey: pool.destinationTokenProgram, isWritable: false },
+  ].map((meta) => ({ ...meta, isSigner: false }));
@@ -0,0 +1,41 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  getMintLen,
+} from "@solana/spl-token";
+
+// Token-2022 mint charging `feeBps` on every transfer, with the provider
+// wallet as mint, fee config and withdraw-withheld authority
+export const createTransferFeeMint = async (provider: anchor.AnchorProvider, feeBps: number) => {
+  const authority = provider.wallet.publicKey;
+  const mint = anchor.web3.Keypair.generate();
+  const space = getMintLen([ExtensionType.TransferFeeConfig]);
+  const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+  const tx = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: authority,
+      newAccountPubkey: mint.publicKey,
+      space,
+      lamports,
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    createInitializeTransferFeeConfigInstruction(
+      mint.publicKey,
+      authority,
+      authority,
+      feeBps,
+      BigInt(1_000_000_000),
+      TOKEN_2022_PROGRAM_ID
+    ),
+    createInitializeMintInstruction(mint.publicKey, 6, authority, null, TOKEN_2022_PROGRAM_ID)
+  );
+  await provider.sendAndConfirm(tx, [mint]);
+  return mint.publicKey;
+};
+
+// Fee withheld when moving `amount` (Token-2022 rounds up)
+export const transferFeeOf = (amount: bigint, feeBps: number) =>
+  (amount * BigInt(feeBps) + BigInt(9_999)) / BigInt(10_000);
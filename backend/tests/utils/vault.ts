@@ -0,0 +1,35 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../../target/types/vault";
+
+const BPF_LOADER_UPGRADEABLE_ID = new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+export const vaultConfigAddress = (vaultProgram: Program<Vault>) =>
+  anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("vault_config")], vaultProgram.programId)[0];
+
+export const vaultProgramDataAddress = (vaultProgram: Program<Vault>) =>
+  anchor.web3.PublicKey.findProgramAddressSync([vaultProgram.programId.toBuffer()], BPF_LOADER_UPGRADEABLE_ID)[0];
+
+// Only the config admin can create vaults; the upgrade authority (the
+// provider wallet) names itself admin once. Test files share one validator,
+// so an existing config is reused; any other failure is rethrown.
+export const initializeVaultConfig = async (vaultProgram: Program<Vault>, provider: anchor.AnchorProvider) => {
+  try {
+    await vaultProgram.methods
+      .initializeConfig(provider.wallet.publicKey)
+      .accounts({
+        config: vaultConfigAddress(vaultProgram),
+        upgradeAuthority: provider.wallet.publicKey,
+        program: vaultProgram.programId,
+        programData: vaultProgramDataAddress(vaultProgram),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  } catch (error: any) {
+    const lines: string[] = [error?.message ?? "", ...(error?.logs ?? [])];
+    if (!lines.some((line) => line.includes("already in use"))) {
+      throw error;
+    }
+    console.log("ℹ️  Vault config already initialized");
+  }
+};
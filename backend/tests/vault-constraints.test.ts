@@ -0,0 +1,322 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { SwapRouter } from "../target/types/swap_router";
+import { TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, createMint, createAccount, mintTo, getAccount } from "@solana/spl-token";
+import { assert } from "chai";
+import { initializeVaultConfig, vaultConfigAddress, vaultProgramDataAddress } from "./utils/vault";
+
+// Every account substitution on the vault paths must be rejected
+describe("Vault Constraint Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const vaultProgram = anchor.workspace.Vault as Program<Vault>;
+  const vaultConfig = vaultConfigAddress(vaultProgram);
+  const vaultProgramData = vaultProgramDataAddress(vaultProgram);
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const JUPITER_PROGRAM_ID = new anchor.web3.PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+
+  let mint: anchor.web3.PublicKey;
+  let otherMint: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+  let vaultToken: anchor.web3.PublicKey;
+  let otherVault: anchor.web3.PublicKey;
+  let otherVaultToken: anchor.web3.PublicKey;
+  let shareMint: anchor.web3.PublicKey;
+  let otherShareMint: anchor.web3.PublicKey;
+  let userShareToken: anchor.web3.PublicKey;
+  let userOtherShareToken: anchor.web3.PublicKey;
+  let userToken: anchor.web3.PublicKey;
+  let userOtherToken: anchor.web3.PublicKey;
+  // Same mint as the vault, but not the vault's canonical account
+  let rogueVaultToken: anchor.web3.PublicKey;
+
+  const vaultPdas = (vaultMint: anchor.web3.PublicKey) => {
+    const [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), vaultMint.toBuffer()],
+      vaultProgram.programId
+    );
+    const [vaultTokenPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_token"), vaultPda.toBuffer()],
+      vaultProgram.programId
+    );
+    const [shareMintPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("share_mint"), vaultPda.toBuffer()],
+      vaultProgram.programId
+    );
+    return [vaultPda, vaultTokenPda, shareMintPda];
+  };
+
+  const initializeVault = (
+    vaultMint: anchor.web3.PublicKey,
+    creator?: anchor.web3.Keypair,
+    swapRouter = swapRouterProgram.programId
+  ) => {
+    const [vaultPda, vaultTokenPda, shareMintPda] = vaultPdas(vaultMint);
+    return vaultProgram.methods
+      .initializeVault("Constraint Vault Share", "axCV", "")
+      .accounts({
+        config: vaultConfig,
+        mint: vaultMint,
+        vault: vaultPda,
+        vaultToken: vaultTokenPda,
+        shareMint: shareMintPda,
+        authority: creator ? creator.publicKey : user,
+        swapRouter,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers(creator ? [creator] : [])
+      .rpc();
+  };
+
+  const deposit = (overrides: Record<string, anchor.web3.PublicKey>) =>
+    vaultProgram.methods
+      .deposit(new anchor.BN(1_000_000), new anchor.BN(0))
+      .accounts({
+        vault,
+        user,
+        userToken,
+        vaultToken,
+        mint,
+        shareMint,
+        userShareToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        ...overrides,
+      })
+      .rpc();
+
+  const withdraw = (overrides: Record<string, anchor.web3.PublicKey>) =>
+    vaultProgram.methods
+      .withdraw(new anchor.BN(1_000), new anchor.BN(0))
+      .accounts({
+        vault,
+        user,
+        shareMint,
+        userShareToken,
+        vaultToken,
+        mint,
+        userToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        ...overrides,
+      })
+      .rpc();
+
+  const executeArbitrage = (overrides: Record<string, anchor.web3.PublicKey>) =>
+    vaultProgram.methods
+      .executeArbitrage(Buffer.from([]), new anchor.BN(1), null, null)
+      .accounts({
+        vault,
+        vaultToken,
+        mint,
+        executor: user,
+        executorToken: userToken,
+        protocolFeeToken: userToken,
+        swapRouterProgram: swapRouterProgram.programId,
+        routerState: anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("router_state")],
+          swapRouterProgram.programId
+        )[0],
+        jupiterProgram: JUPITER_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        ...overrides,
+      })
+      .rpc();
+
+  const expectRejected = async (action: Promise<unknown>, message: string) => {
+    try {
+      await action;
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, message);
+      console.log("✅ Correctly rejected:", error.error.errorMessage);
+    }
+  };
+
+  before(async () => {
+    await initializeVaultConfig(vaultProgram, provider);
+
+    mint = await createMint(provider.connection, payer, user, null, 6);
+    otherMint = await createMint(provider.connection, payer, user, null, 6);
+    [vault, vaultToken, shareMint] = vaultPdas(mint);
+    [otherVault, otherVaultToken, otherShareMint] = vaultPdas(otherMint);
+    await initializeVault(mint);
+    await initializeVault(otherMint);
+
+    userShareToken = await createAccount(
+      provider.connection, payer, shareMint, user, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    userOtherShareToken = await createAccount(
+      provider.connection, payer, otherShareMint, user, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    userToken = await createAccount(provider.connection, payer, mint, user);
+    userOtherToken = await createAccount(provider.connection, payer, otherMint, user);
+    rogueVaultToken = await createAccount(provider.connection, payer, mint, user, anchor.web3.Keypair.generate());
+    await mintTo(provider.connection, payer, mint, userToken, user, 1_000_000_000);
+    await mintTo(provider.connection, payer, otherMint, userOtherToken, user, 1_000_000_000);
+
+    // A legitimate position to withdraw from
+    await deposit({});
+  });
+
+  it("Records the canonical token account at initialization", async () => {
+    const vaultAccount = await vaultProgram.account.vault.fetch(vault);
+    assert.ok(vaultAccount.tokenAccount.equals(vaultToken));
+    const tokenAccount = await getAccount(provider.connection, vaultToken);
+    assert.ok(tokenAccount.owner.equals(vault));
+    assert.ok(tokenAccount.mint.equals(mint));
+  });
+
+  it("Deposit rejects a caller-controlled vault token account", async () => {
+    await expectRejected(deposit({ vaultToken: rogueVaultToken }), "canonical token account");
+  });
+
+  it("Deposit rejects another vault's token account", async () => {
+    await expectRejected(deposit({ vaultToken: otherVaultToken }), "canonical token account");
+  });
+
+  it("Deposit rejects a user token account of another mint", async () => {
+    await expectRejected(deposit({ userToken: userOtherToken }), "Invalid token mint for this vault");
+  });
+
+  it("Deposit rejects a mint other than the vault's", async () => {
+    await expectRejected(deposit({ mint: otherMint }), "Invalid token mint for this vault");
+  });
+
+  it("Deposit rejects a vault derived from another mint", async () => {
+    await expectRejected(
+      deposit({ vault: otherVault, vaultToken: otherVaultToken }),
+      "Invalid token mint for this vault"
+    );
+  });
+
+  it("Deposit rejects another vault's share mint", async () => {
+    await expectRejected(
+      deposit({ shareMint: otherShareMint, userShareToken: userOtherShareToken }),
+      "not this vault's share mint"
+    );
+  });
+
+  it("Withdraw rejects burning another vault's shares", async () => {
+    await expectRejected(
+      withdraw({ shareMint: otherShareMint, userShareToken: userOtherShareToken }),
+      "not this vault's share mint"
+    );
+  });
+
+  it("Withdraw rejects a caller-controlled vault token account", async () => {
+    await expectRejected(withdraw({ vaultToken: rogueVaultToken }), "canonical token account");
+  });
+
+  it("Withdraw rejects a user token account of another mint", async () => {
+    await expectRejected(withdraw({ userToken: userOtherToken }), "Invalid token mint for this vault");
+  });
+
+  it("Arbitrage rejects a caller-controlled vault token account", async () => {
+    await expectRejected(executeArbitrage({ vaultToken: rogueVaultToken }), "canonical token account");
+  });
+
+  it("Arbitrage rejects an executor token account of another mint", async () => {
+    await expectRejected(executeArbitrage({ executorToken: userOtherToken }), "Invalid token mint for this vault");
+  });
+
+  it("Arbitrage rejects a protocol fee account not owned by the fee recipient", async () => {
+    const strangerToken = await createAccount(
+      provider.connection, payer, mint, anchor.web3.Keypair.generate().publicKey
+    );
+    await expectRejected(
+      executeArbitrage({ protocolFeeToken: strangerToken }),
+      "not owned by the protocol fee recipient"
+    );
+  });
+
+  it("Arbitrage rejects a protocol fee account of another mint", async () => {
+    await expectRejected(
+      executeArbitrage({ protocolFeeToken: userOtherToken }),
+      "Invalid token mint for this vault"
+    );
+  });
+
+  it("Arbitrage rejects a swap router other than the vault's", async () => {
+    await expectRejected(
+      executeArbitrage({ swapRouterProgram: anchor.web3.Keypair.generate().publicKey }),
+      "Invalid swap router program"
+    );
+  });
+
+  it("Flash borrow rejects a caller-controlled vault token account", async () => {
+    await expectRejected(
+      vaultProgram.methods
+        .flashBorrow(new anchor.BN(1_000))
+        .accounts({
+          vault,
+          vaultToken: rogueVaultToken,
+          mint,
+          borrower: user,
+          borrowerToken: userToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .rpc(),
+      "canonical token account"
+    );
+  });
+
+  it("Only the config admin can create vaults", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const strangerMint = await createMint(provider.connection, payer, user, null, 6);
+    await expectRejected(initializeVault(strangerMint, stranger), "Invalid vault authority");
+  });
+
+  it("Vault creation rejects a swap router other than the router program", async () => {
+    const routerMint = await createMint(provider.connection, payer, user, null, 6);
+    await expectRejected(
+      initializeVault(routerMint, undefined, JUPITER_PROGRAM_ID),
+      "Invalid swap router program"
+    );
+  });
+
+  it("Only the config admin can hand over vault creation", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await expectRejected(
+      vaultProgram.methods
+        .setConfigAdmin(stranger.publicKey)
+        .accounts({ config: vaultConfig, admin: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "Invalid vault authority"
+    );
+  });
+
+  it("The vault config can only be initialized once", async () => {
+    try {
+      await vaultProgram.methods
+        .initializeConfig(anchor.web3.Keypair.generate().publicKey)
+        .accounts({
+          config: vaultConfig,
+          upgradeAuthority: user,
+          program: vaultProgram.programId,
+          programData: vaultProgramData,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.notEqual(error.message, "Should have thrown an error");
+    }
+    const config = await vaultProgram.account.vaultConfig.fetch(vaultConfig);
+    assert.ok(config.admin.equals(user));
+  });
+});
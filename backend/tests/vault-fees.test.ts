@@ -0,0 +1,206 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { SwapRouter } from "../target/types/swap_router";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createAccount,
+  createMint,
+  getAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { initializeVaultConfig, vaultConfigAddress } from "./utils/vault";
+import { JUPITER_PROGRAM_ID, createMockPool, mockRouteAccounts, mockRoutePayload } from "./utils/jupiter";
+
+describe("Vault Fee Configuration Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const vaultProgram = anchor.workspace.Vault as Program<Vault>;
+  const vaultConfig = vaultConfigAddress(vaultProgram);
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const authority = provider.wallet.publicKey;
+  const treasury = anchor.web3.Keypair.generate().publicKey;
+
+  let mint: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+
+  const updateFees = (executorFeeBps: number, protocolFeeBps: number, recipient = treasury) =>
+    vaultProgram.methods
+      .updateFees(executorFeeBps, protocolFeeBps, recipient)
+      .accounts({ vault, authority });
+
+  const expectRejected = async (action: Promise<unknown>, message: string) => {
+    try {
+      await action;
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, message);
+      console.log("✅ Correctly rejected:", error.error.errorMessage);
+    }
+  };
+
+  before(async () => {
+    await initializeVaultConfig(vaultProgram, provider);
+
+    mint = await createMint(provider.connection, payer, authority, null, 6);
+    [vault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), mint.toBuffer()],
+      vaultProgram.programId
+    );
+
+    await vaultProgram.methods
+      .initializeVault("Fee Vault Share", "axFEE", "")
+      .accounts({
+        config: vaultConfig,
+        mint,
+        vault,
+        vaultToken: anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("vault_token"), vault.toBuffer()],
+          vaultProgram.programId
+        )[0],
+        shareMint: anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("share_mint"), vault.toBuffer()],
+          vaultProgram.programId
+        )[0],
+        authority,
+        swapRouter: swapRouterProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("New vaults keep the 10% executor fee and no protocol fee", async () => {
+    const vaultAccount = await vaultProgram.account.vault.fetch(vault);
+    assert.equal(vaultAccount.executorFeeBps, 1_000);
+    assert.equal(vaultAccount.protocolFeeBps, 0);
+    assert.ok(vaultAccount.protocolFeeRecipient.equals(authority));
+  });
+
+  it("Authority sets a three-way split", async () => {
+    await updateFees(500, 1_500).rpc();
+
+    const vaultAccount = await vaultProgram.account.vault.fetch(vault);
+    assert.equal(vaultAccount.executorFeeBps, 500);
+    assert.equal(vaultAccount.protocolFeeBps, 1_500);
+    assert.ok(vaultAccount.protocolFeeRecipient.equals(treasury));
+    console.log("✅ Executor 5%, protocol 15%, depositors 80%");
+  });
+
+  it("Rejects an executor fee above its cap", async () => {
+    await expectRejected(updateFees(3_001, 0).rpc(), "Fee configuration above the limits");
+  });
+
+  it("Rejects a protocol fee above its cap", async () => {
+    await expectRejected(updateFees(0, 3_001).rpc(), "Fee configuration above the limits");
+  });
+
+  it("Rejects fees leaving depositors less than half the profit", async () => {
+    await expectRejected(updateFees(3_000, 2_001).rpc(), "Fee configuration above the limits");
+  });
+
+  it("Rejects a protocol fee without a recipient", async () => {
+    await expectRejected(
+      updateFees(500, 1_500, anchor.web3.PublicKey.default).rpc(),
+      "Protocol fee requires a fee recipient"
+    );
+  });
+
+  it("Allows no recipient while the protocol fee is off", async () => {
+    await updateFees(500, 0, anchor.web3.PublicKey.default).rpc();
+
+    const vaultAccount = await vaultProgram.account.vault.fetch(vault);
+    assert.equal(vaultAccount.protocolFeeBps, 0);
+    assert.ok(vaultAccount.protocolFeeRecipient.equals(anchor.web3.PublicKey.default));
+  });
+
+  it("Executes an arbitrage without a protocol fee account while the fee is off", async () => {
+    const pda = (seeds: Buffer[]) =>
+      anchor.web3.PublicKey.findProgramAddressSync(seeds, vaultProgram.programId)[0];
+    const vaultToken = pda([Buffer.from("vault_token"), vault.toBuffer()]);
+    const shareMint = pda([Buffer.from("share_mint"), vault.toBuffer()]);
+    const routerState = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("router_state")],
+      swapRouterProgram.programId
+    )[0];
+
+    try {
+      await swapRouterProgram.methods
+        .initializeRouter(30)
+        .accounts({ routerState, authority, systemProgram: anchor.web3.SystemProgram.programId })
+        .rpc();
+    } catch (error) {
+      console.log("ℹ️  Router already initialized");
+    }
+    await swapRouterProgram.methods
+      .registerVaultProgram(vaultProgram.programId)
+      .accounts({ routerState, authority })
+      .rpc();
+
+    const userToken = await createAccount(provider.connection, payer, mint, authority);
+    const userShareToken = await createAccount(
+      provider.connection, payer, shareMint, authority, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(provider.connection, payer, mint, userToken, authority, 10_000_000);
+    await vaultProgram.methods
+      .deposit(new anchor.BN(10_000_000), new anchor.BN(0))
+      .accounts({
+        vault,
+        user: authority,
+        userToken,
+        vaultToken,
+        mint,
+        shareMint,
+        userShareToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    // Round trip through the mock paying back 100_000 more than it takes
+    const pool = await createMockPool(provider, mint, mint, 10_000_000);
+    const vaultBefore = (await getAccount(provider.connection, vaultToken)).amount;
+    const executorBefore = (await getAccount(provider.connection, userToken)).amount;
+
+    await vaultProgram.methods
+      .executeArbitrage(mockRoutePayload(1_000_000, 1_100_000), new anchor.BN(1), null, null)
+      .accounts({
+        vault,
+        vaultToken,
+        mint,
+        executor: authority,
+        executorToken: userToken,
+        swapRouterProgram: swapRouterProgram.programId,
+        routerState,
+        jupiterProgram: JUPITER_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(mockRouteAccounts(pool, vault, vaultToken, vaultToken))
+      .rpc();
+
+    // 5% executor fee, no protocol fee, the rest stays with depositors
+    assert.equal((await getAccount(provider.connection, userToken)).amount - executorBefore, BigInt(5_000));
+    assert.equal((await getAccount(provider.connection, vaultToken)).amount - vaultBefore, BigInt(95_000));
+    console.log("✅ Arbitrage settled without a protocol fee account");
+  });
+
+  it("Only the vault authority can update fees", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    await expectRejected(
+      vaultProgram.methods
+        .updateFees(0, 3_000, stranger.publicKey)
+        .accounts({ vault, authority: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "Invalid vault authority"
+    );
+  });
+});
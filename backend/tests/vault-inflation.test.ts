@@ -0,0 +1,315 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { SwapRouter } from "../target/types/swap_router";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { initializeVaultConfig, vaultConfigAddress } from "./utils/vault";
+
+// Randomized checks that donations to the vault token account cannot dilute
+// depositors. Runs are seeded so a failure can be replayed with VAULT_PROPERTY_SEED
+describe("Vault Inflation Attack Property Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const vaultProgram = anchor.workspace.Vault as Program<Vault>;
+  const vaultConfig = vaultConfigAddress(vaultProgram);
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const authority = provider.wallet.publicKey;
+
+  const SEED = Number(process.env.VAULT_PROPERTY_SEED ?? 0x5eed);
+  const ATTACK_RUNS = 5;
+  const RANDOM_STEPS = 25;
+
+  // mulberry32
+  const rng = (() => {
+    let state = SEED >>> 0;
+    return () => {
+      state = (state + 0x6d2b79f5) >>> 0;
+      let t = state;
+      t = Math.imul(t ^ (t >>> 15), t | 1);
+      t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+      return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+    };
+  })();
+  const randomBetween = (min: number, max: number) => min + Math.floor(rng() * (max - min + 1));
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, vaultProgram.programId)[0];
+
+  type Holder = {
+    keypair: anchor.web3.Keypair;
+    token: anchor.web3.PublicKey;
+    shareToken: anchor.web3.PublicKey;
+  };
+
+  type TestVault = {
+    mint: anchor.web3.PublicKey;
+    vault: anchor.web3.PublicKey;
+    vaultToken: anchor.web3.PublicKey;
+    shareMint: anchor.web3.PublicKey;
+  };
+
+  const tokenBalance = async (account: anchor.web3.PublicKey, programId = TOKEN_PROGRAM_ID) =>
+    BigInt((await getAccount(provider.connection, account, undefined, programId)).amount);
+
+  const newVault = async (): Promise<TestVault> => {
+    const mint = await createMint(provider.connection, payer, authority, null, 6);
+    const vault = pda([Buffer.from("vault"), mint.toBuffer()]);
+    const vaultToken = pda([Buffer.from("vault_token"), vault.toBuffer()]);
+    const shareMint = pda([Buffer.from("share_mint"), vault.toBuffer()]);
+    await vaultProgram.methods
+      .initializeVault("Property Vault Share", "axPV", "")
+      .accounts({
+        config: vaultConfig,
+        mint,
+        vault,
+        vaultToken,
+        shareMint,
+        authority,
+        swapRouter: swapRouterProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    return { mint, vault, vaultToken, shareMint };
+  };
+
+  const newHolder = async (v: TestVault, funds: number): Promise<Holder> => {
+    const keypair = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(keypair.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const token = await createAccount(provider.connection, payer, v.mint, keypair.publicKey);
+    const shareToken = await createAccount(
+      provider.connection, payer, v.shareMint, keypair.publicKey, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(provider.connection, payer, v.mint, token, authority, funds);
+    return { keypair, token, shareToken };
+  };
+
+  const deposit = (v: TestVault, holder: Holder, amount: bigint, minSharesOut = 0n) =>
+    vaultProgram.methods
+      .deposit(new anchor.BN(amount.toString()), new anchor.BN(minSharesOut.toString()))
+      .accounts({
+        vault: v.vault,
+        user: holder.keypair.publicKey,
+        userToken: holder.token,
+        vaultToken: v.vaultToken,
+        mint: v.mint,
+        shareMint: v.shareMint,
+        userShareToken: holder.shareToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([holder.keypair])
+      .rpc();
+
+  const withdraw = (v: TestVault, holder: Holder, shares: bigint) =>
+    vaultProgram.methods
+      .withdraw(new anchor.BN(shares.toString()), new anchor.BN(0))
+      .accounts({
+        vault: v.vault,
+        user: holder.keypair.publicKey,
+        shareMint: v.shareMint,
+        userShareToken: holder.shareToken,
+        vaultToken: v.vaultToken,
+        mint: v.mint,
+        userToken: holder.token,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([holder.keypair])
+      .rpc();
+
+  // Direct transfer to the vault token account, bypassing deposit
+  const donate = (v: TestVault, amount: bigint) =>
+    mintTo(provider.connection, payer, v.mint, v.vaultToken, authority, amount);
+
+  const view = async (v: TestVault, method: "previewDeposit" | "convertToAssets", amount: bigint) =>
+    BigInt(
+      (await vaultProgram.methods[method](new anchor.BN(amount.toString()))
+        .accounts({ vault: v.vault, vaultToken: v.vaultToken })
+        .view()).toString()
+    );
+
+  const redeemableValue = async (v: TestVault, holder: Holder) =>
+    view(v, "convertToAssets", await tokenBalance(holder.shareToken, TOKEN_2022_PROGRAM_ID));
+
+  const expectError = async (action: Promise<unknown>, message: string) => {
+    try {
+      await action;
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, message);
+    }
+  };
+
+  before(async () => {
+    console.log("ℹ️  Property seed:", SEED);
+
+    await initializeVaultConfig(vaultProgram, provider);
+  });
+
+  it("A first-depositor donation never profits the attacker", async () => {
+    for (let run = 0; run < ATTACK_RUNS; run++) {
+      const v = await newVault();
+      const attacker = await newHolder(v, 1_000_000_000_000);
+      const victim = await newHolder(v, 1_000_000_000);
+
+      const seedDeposit = BigInt(randomBetween(1, 1_000));
+      const donation = BigInt(randomBetween(1, 100_000)) * 1_000_000n;
+      const victimDeposit = BigInt(randomBetween(1_000_000, 1_000_000_000));
+
+      await deposit(v, attacker, seedDeposit);
+      await donate(v, donation);
+
+      const victimBefore = await tokenBalance(victim.token);
+      const expectedShares = await view(v, "previewDeposit", victimDeposit);
+      if (expectedShares == 0n) {
+        // Too small to price: rejected outright rather than minting nothing
+        await expectError(deposit(v, victim, victimDeposit), "Deposit too small to mint any shares");
+        assert.equal(await tokenBalance(victim.token), victimBefore);
+        continue;
+      }
+      await deposit(v, victim, victimDeposit);
+
+      // The victim loses at most one share's worth of rounding, plus one unit
+      // for rounding the value itself
+      const sharePrice = (seedDeposit + donation + 1_000n) / (seedDeposit + 1_000n) + 1n;
+      const victimValue = await redeemableValue(v, victim);
+      assert.ok(victimValue <= victimDeposit);
+      assert.ok(
+        victimDeposit - victimValue <= sharePrice + 1n,
+        `run ${run}: victim lost ${victimDeposit - victimValue} with share price ${sharePrice}`
+      );
+
+      // The attacker cannot get back more than it put in
+      const attackerBefore = await tokenBalance(attacker.token);
+      await withdraw(v, attacker, await tokenBalance(attacker.shareToken, TOKEN_2022_PROGRAM_ID));
+      const recovered = (await tokenBalance(attacker.token)) - attackerBefore;
+      assert.ok(
+        recovered <= seedDeposit + donation,
+        `run ${run}: attacker recovered ${recovered} of ${seedDeposit + donation}`
+      );
+      console.log(
+        `✅ run ${run}: donation ${donation}, victim loss ${victimDeposit - victimValue}, attacker loss ${seedDeposit + donation - recovered}`
+      );
+    }
+  });
+
+  it("Deposits, withdrawals and donations never lower an existing holder's value", async () => {
+    const v = await newVault();
+    const holders: Holder[] = [];
+    for (let i = 0; i < 3; i++) {
+      holders.push(await newHolder(v, 1_000_000_000_000));
+    }
+
+    for (let step = 0; step < RANDOM_STEPS; step++) {
+      const before = await Promise.all(holders.map((h) => redeemableValue(v, h)));
+      const actor = holders[randomBetween(0, holders.length - 1)];
+      const action = rng();
+
+      if (action < 0.5) {
+        const amount = BigInt(randomBetween(1, 10_000_000_000));
+        if ((await view(v, "previewDeposit", amount)) == 0n) {
+          await expectError(deposit(v, actor, amount), "Deposit too small to mint any shares");
+        } else {
+          await deposit(v, actor, amount);
+        }
+      } else if (action < 0.75) {
+        const shares = await tokenBalance(actor.shareToken, TOKEN_2022_PROGRAM_ID);
+        if (shares > 0n) {
+          await withdraw(v, actor, BigInt(randomBetween(1, Number(shares))));
+        }
+      } else {
+        await donate(v, BigInt(randomBetween(1, 1_000_000_000)));
+      }
+
+      const after = await Promise.all(holders.map((h) => redeemableValue(v, h)));
+      holders.forEach((holder, i) => {
+        if (holder === actor) return;
+        assert.ok(after[i] >= before[i], `step ${step}: holder ${i} went from ${before[i]} to ${after[i]}`);
+      });
+    }
+    console.log(`✅ ${RANDOM_STEPS} random steps without diluting any holder`);
+  });
+
+  it("min_shares_out protects a deposit from a front-running donation", async () => {
+    const v = await newVault();
+    const attacker = await newHolder(v, 1_000_000_000_000);
+    const victim = await newHolder(v, 1_000_000_000);
+
+    await deposit(v, attacker, 1n);
+    const quoted = await view(v, "previewDeposit", 1_000_000n);
+
+    // The donation lands between the quote and the deposit
+    await donate(v, 10_000_000_000n);
+    const victimBefore = await tokenBalance(victim.token);
+    await expectError(deposit(v, victim, 1_000_000n, quoted), "Slippage tolerance exceeded");
+    assert.equal(await tokenBalance(victim.token), victimBefore);
+    console.log("✅ Deposit reverted instead of minting fewer shares than quoted");
+  });
+
+  it("Withdrawing by assets rounds the burned shares up", async () => {
+    const v = await newVault();
+    const holder = await newHolder(v, 1_000_000_000);
+    await deposit(v, holder, 100_000_000n);
+    // 1.5 tokens per share
+    await donate(v, 50_000_000n);
+
+    const sharesBefore = await tokenBalance(holder.shareToken, TOKEN_2022_PROGRAM_ID);
+    const tokensBefore = await tokenBalance(holder.token);
+    await expectError(
+      vaultProgram.methods
+        .withdrawAssets(new anchor.BN(1), new anchor.BN(0))
+        .accounts({
+          vault: v.vault,
+          user: holder.keypair.publicKey,
+          shareMint: v.shareMint,
+          userShareToken: holder.shareToken,
+          vaultToken: v.vaultToken,
+          mint: v.mint,
+          userToken: holder.token,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([holder.keypair])
+        .rpc(),
+      "Slippage tolerance exceeded"
+    );
+
+    await vaultProgram.methods
+      .withdrawAssets(new anchor.BN(1), new anchor.BN(1))
+      .accounts({
+        vault: v.vault,
+        user: holder.keypair.publicKey,
+        shareMint: v.shareMint,
+        userShareToken: holder.shareToken,
+        vaultToken: v.vaultToken,
+        mint: v.mint,
+        userToken: holder.token,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([holder.keypair])
+      .rpc();
+
+    // 1 token is worth 0.66 shares, so a whole share is burned for it
+    assert.equal(sharesBefore - (await tokenBalance(holder.shareToken, TOKEN_2022_PROGRAM_ID)), 1n);
+    assert.equal((await tokenBalance(holder.token)) - tokensBefore, 1n);
+    console.log("✅ Withdraw-by-assets rounded in the vault's favor");
+  });
+});
@@ -0,0 +1,277 @@
+import * as dotenv from "dotenv";
+dotenv.config();
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Vault } from "../target/types/vault";
+import { SwapRouter } from "../target/types/swap_router";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createMint,
+  createAccount,
+  mintTo,
+  getAccount,
+  getMint,
+  getTokenMetadata,
+  transferChecked,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { initializeVaultConfig, vaultConfigAddress } from "./utils/vault";
+
+describe("Vault Share Token Tests", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const vaultProgram = anchor.workspace.Vault as Program<Vault>;
+  const vaultConfig = vaultConfigAddress(vaultProgram);
+  const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
+  const payer = provider.wallet.payer;
+  const user = provider.wallet.publicKey;
+  const treasury = anchor.web3.Keypair.generate();
+
+  let mint: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+  let vaultToken: anchor.web3.PublicKey;
+  let shareMint: anchor.web3.PublicKey;
+  let userToken: anchor.web3.PublicKey;
+  let userShareToken: anchor.web3.PublicKey;
+  let treasuryToken: anchor.web3.PublicKey;
+  let treasuryShareToken: anchor.web3.PublicKey;
+
+  const pda = (seeds: Buffer[]) =>
+    anchor.web3.PublicKey.findProgramAddressSync(seeds, vaultProgram.programId)[0];
+
+  const shareBalance = async (account: anchor.web3.PublicKey) =>
+    BigInt((await getAccount(provider.connection, account, undefined, TOKEN_2022_PROGRAM_ID)).amount);
+
+  const deposit = (amount: number) =>
+    vaultProgram.methods
+      .deposit(new anchor.BN(amount), new anchor.BN(0))
+      .accounts({
+        vault,
+        user,
+        userToken,
+        vaultToken,
+        mint,
+        shareMint,
+        userShareToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    await initializeVaultConfig(vaultProgram, provider);
+
+    mint = await createMint(provider.connection, payer, user, null, 6);
+    vault = pda([Buffer.from("vault"), mint.toBuffer()]);
+    vaultToken = pda([Buffer.from("vault_token"), vault.toBuffer()]);
+    shareMint = pda([Buffer.from("share_mint"), vault.toBuffer()]);
+
+    await vaultProgram.methods
+      .initializeVault("AtomX Vault Share", "axVLT", "https://atomx.example/share.json")
+      .accounts({
+        config: vaultConfig,
+        mint,
+        vault,
+        vaultToken,
+        shareMint,
+        authority: user,
+        swapRouter: swapRouterProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    userToken = await createAccount(provider.connection, payer, mint, user);
+    userShareToken = await createAccount(
+      provider.connection, payer, shareMint, user, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    treasuryToken = await createAccount(provider.connection, payer, mint, treasury.publicKey);
+    treasuryShareToken = await createAccount(
+      provider.connection, payer, shareMint, treasury.publicKey, undefined, undefined, TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(provider.connection, payer, mint, userToken, user, 1_000_000_000);
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(treasury.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+  });
+
+  it("Creates a share mint with on-mint metadata", async () => {
+    const share = await getMint(provider.connection, shareMint, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.ok(share.mintAuthority!.equals(vault));
+    assert.equal(share.decimals, 6);
+    assert.equal(share.supply, 0n);
+
+    const metadata = await getTokenMetadata(provider.connection, shareMint);
+    assert.equal(metadata!.name, "AtomX Vault Share");
+    assert.equal(metadata!.symbol, "axVLT");
+    assert.equal(metadata!.uri, "https://atomx.example/share.json");
+    assert.ok(metadata!.updateAuthority!.equals(user));
+    console.log("✅ Share mint metadata:", metadata!.name, metadata!.symbol);
+  });
+
+  it("Rejects share metadata over the Metaplex limits", async () => {
+    const otherMint = await createMint(provider.connection, payer, user, null, 6);
+    const otherVault = pda([Buffer.from("vault"), otherMint.toBuffer()]);
+    try {
+      await vaultProgram.methods
+        .initializeVault("AtomX Vault Share", "WAY_TOO_LONG_SYMBOL", "")
+        .accounts({
+          config: vaultConfig,
+          mint: otherMint,
+          vault: otherVault,
+          vaultToken: pda([Buffer.from("vault_token"), otherVault.toBuffer()]),
+          shareMint: pda([Buffer.from("share_mint"), otherVault.toBuffer()]),
+          authority: user,
+          swapRouter: swapRouterProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Share token name, symbol or uri");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Mints shares on deposit and tracks the supply", async () => {
+    await deposit(100_000_000);
+
+    assert.equal(await shareBalance(userShareToken), 100_000_000n);
+    const share = await getMint(provider.connection, shareMint, undefined, TOKEN_2022_PROGRAM_ID);
+    const vaultAccount = await vaultProgram.account.vault.fetch(vault);
+    assert.equal(vaultAccount.totalShares.toString(), share.supply.toString());
+    console.log("✅ Shares minted:", share.supply.toString());
+  });
+
+  it("Previews match the executed deposit", async () => {
+    // Simulate profit so shares are no longer 1:1
+    await mintTo(provider.connection, payer, mint, vaultToken, user, 50_000_000);
+
+    const preview = await vaultProgram.methods
+      .previewDeposit(new anchor.BN(30_000_000))
+      .accounts({ vault, vaultToken })
+      .view();
+    const before = await shareBalance(userShareToken);
+    await deposit(30_000_000);
+
+    assert.equal((await shareBalance(userShareToken)) - before, BigInt(preview.toString()));
+    // 30 tokens at ~1.5 tokens per share, priced with the 1000/1000 virtual offset
+    assert.equal(BigInt(preview.toString()), (30_000_000n * 100_001_000n) / 150_001_000n);
+    console.log("✅ preview_deposit:", preview.toString());
+  });
+
+  it("Converts between shares and assets with the right rounding", async () => {
+    const view = (method: "convertToShares" | "convertToAssets" | "previewWithdraw", amount: number) =>
+      vaultProgram.methods[method](new anchor.BN(amount)).accounts({ vault, vaultToken }).view();
+
+    // ~120 shares backed by 180 tokens
+    const totalShares = BigInt((await vaultProgram.account.vault.fetch(vault)).totalShares.toString()) + 1_000n;
+    const totalAssets = 180_000_000n + 1_000n;
+    assert.equal(BigInt((await view("convertToAssets", 3_000)).toString()), (3_000n * totalAssets) / totalShares);
+    assert.equal(BigInt((await view("convertToShares", 3_000)).toString()), (3_000n * totalShares) / totalAssets);
+    // 1 token is 0.66 shares: converted down, but withdrawing it costs a whole share
+    assert.equal((await view("convertToShares", 1)).toNumber(), 0);
+    assert.equal((await view("previewWithdraw", 1)).toNumber(), 1);
+    console.log("✅ Conversions round in the vault's favor");
+  });
+
+  it("Rejects a withdrawal paying less than min_assets_out", async () => {
+    const preview = await vaultProgram.methods
+      .convertToAssets(new anchor.BN(3_000_000))
+      .accounts({ vault, vaultToken })
+      .view();
+    try {
+      await vaultProgram.methods
+        .withdraw(new anchor.BN(3_000_000), preview.addn(1))
+        .accounts({
+          vault,
+          user,
+          shareMint,
+          userShareToken,
+          vaultToken,
+          mint,
+          userToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "Slippage tolerance exceeded");
+      console.log("✅ Correctly rejected");
+    }
+  });
+
+  it("Shares move between wallets and the new holder can withdraw", async () => {
+    await transferChecked(
+      provider.connection,
+      payer,
+      userShareToken,
+      shareMint,
+      treasuryShareToken,
+      user,
+      40_000_000,
+      6,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(await shareBalance(treasuryShareToken), 40_000_000n);
+
+    const expected = await vaultProgram.methods
+      .convertToAssets(new anchor.BN(40_000_000))
+      .accounts({ vault, vaultToken })
+      .view();
+
+    await vaultProgram.methods
+      .withdraw(new anchor.BN(40_000_000), expected)
+      .accounts({
+        vault,
+        user: treasury.publicKey,
+        shareMint,
+        userShareToken: treasuryShareToken,
+        vaultToken,
+        mint,
+        userToken: treasuryToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([treasury])
+      .rpc();
+
+    assert.equal(await shareBalance(treasuryShareToken), 0n);
+    const received = (await getAccount(provider.connection, treasuryToken)).amount;
+    assert.equal(received.toString(), expected.toString());
+    console.log("✅ Treasury redeemed transferred shares for:", received.toString());
+  });
+
+  it("Cannot burn shares held by someone else", async () => {
+    try {
+      await vaultProgram.methods
+        .withdraw(new anchor.BN(1_000_000), new anchor.BN(0))
+        .accounts({
+          vault,
+          user: treasury.publicKey,
+          shareMint,
+          userShareToken,
+          vaultToken,
+          mint,
+          userToken: treasuryToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([treasury])
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      assert.include(error.error.errorMessage, "token owner constraint");
+      console.log("✅ Correctly rejected");
+    }
+  });
+});
@@ -5,23 +5,37 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Vault } from "../target/types/vault";
 import { SwapRouter } from "../target/types/swap_router";
+import { TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, createMint } from "@solana/spl-token";
 import { assert } from "chai";
+import { initializeVaultConfig, vaultConfigAddress } from "./utils/vault";
 
 describe("Vault Simple Tests", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
 
   const vaultProgram = anchor.workspace.Vault as Program<Vault>;
+  const vaultConfig = vaultConfigAddress(vaultProgram);
   const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
 
+  let mint: anchor.web3.PublicKey;
   let vaultPda: anchor.web3.PublicKey;
 
   before(async () => {
+    await initializeVaultConfig(vaultProgram, provider);
+
     console.log("🔧 Setting up test environment...");
 
-    // Calculate vault PDA
+    mint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      provider.wallet.publicKey,
+      null,
+      9
+    );
+
+    // Calculate vault PDA (one vault per mint)
     [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("vault")],
+      [Buffer.from("vault"), mint.toBuffer()],
       vaultProgram.programId
     );
 
@@ -34,11 +48,23 @@ describe("Vault Simple Tests", () => {
 
     try {
       const tx = await vaultProgram.methods
-        .initializeVault()
+        .initializeVault("AtomX Vault Share", "axVLT", "")
         .accounts({
+          config: vaultConfig,
+          mint,
           vault: vaultPda,
+          vaultToken: anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_token"), vaultPda.toBuffer()],
+            vaultProgram.programId
+          )[0],
+          shareMint: anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("share_mint"), vaultPda.toBuffer()],
+            vaultProgram.programId
+          )[0],
           authority: provider.wallet.publicKey,
           swapRouter: swapRouterProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          shareTokenProgram: TOKEN_2022_PROGRAM_ID,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
         .rpc();
@@ -50,6 +76,7 @@ describe("Vault Simple Tests", () => {
       const vaultAccount = await vaultProgram.account.vault.fetch(vaultPda);
       
       assert.ok(vaultAccount.authority.equals(provider.wallet.publicKey));
+      assert.ok(vaultAccount.mint.equals(mint));
       assert.ok(vaultAccount.swapRouter.equals(swapRouterProgram.programId));
       assert.equal(vaultAccount.totalShares.toNumber(), 0);
       
@@ -7,6 +7,7 @@ dotenv.config();
 
 import { 
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   createMint,
   createAccount,
   mintTo,
@@ -15,21 +16,35 @@ import {
   createAssociatedTokenAccount
 } from "@solana/spl-token";
 import { assert } from "chai";
+import { initializeVaultConfig, vaultConfigAddress } from "./utils/vault";
 
 describe("Vault Tests", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
 
   const vaultProgram = anchor.workspace.Vault as Program<Vault>;
+  const vaultConfig = vaultConfigAddress(vaultProgram);
   const swapRouterProgram = anchor.workspace.SwapRouter as Program<SwapRouter>;
 
   let mint: anchor.web3.PublicKey;
   let vaultTokenAccount: anchor.web3.PublicKey;
   let userTokenAccount: anchor.web3.PublicKey;
   let vaultPda: anchor.web3.PublicKey;
-  let userPositionPda: anchor.web3.PublicKey;
+  let shareMint: anchor.web3.PublicKey;
+  let userShareToken: anchor.web3.PublicKey;
+
+  const shareMintFor = (vault: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("share_mint"), vault.toBuffer()],
+      vaultProgram.programId
+    )[0];
+
+  const shareBalance = async () =>
+    BigInt((await getAccount(provider.connection, userShareToken, undefined, TOKEN_2022_PROGRAM_ID)).amount);
 
   before(async () => {
+    await initializeVaultConfig(vaultProgram, provider);
+
     console.log("🔧 Setting up test environment...");
 
     // Créer un token mint pour les tests
@@ -42,9 +57,9 @@ describe("Vault Tests", () => {
     );
     console.log(" Mint created:", mint.toString());
 
-    // Calculer le vault PDA d'abord
+    // Calculer le vault PDA d'abord (un vault par mint)
     [vaultPda] = await anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("vault")],
+      [Buffer.from("vault"), mint.toBuffer()],
       vaultProgram.programId
     );
 
@@ -60,17 +75,11 @@ describe("Vault Tests", () => {
     );
     console.log(" User token account created:", userTokenAccount.toString());
     
-    // Create vault's token account (also owned by user for simplicity)
-    vaultTokenAccount = await createAccount(
-      provider.connection,
-      provider.wallet.payer,
-      mint,
-      provider.wallet.publicKey,
-      undefined,
-      undefined,
-      TOKEN_PROGRAM_ID
+    // Canonical vault token account, created by initialize_vault
+    [vaultTokenAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_token"), vaultPda.toBuffer()],
+      vaultProgram.programId
     );
-    console.log(" Vault token account created:", vaultTokenAccount.toString());
 
     // Mint 1000 tokens à l'user pour tester
     await mintTo(
@@ -83,11 +92,7 @@ describe("Vault Tests", () => {
     );
     console.log(" Minted 1000 tokens to user");
 
-    // Calculer le user position PDA
-    [userPositionPda] = await anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("position"), provider.wallet.publicKey.toBuffer()],
-      vaultProgram.programId
-    );
+    shareMint = shareMintFor(vaultPda);
 
     console.log(" Setup complete!\n");
   });
@@ -96,11 +101,17 @@ describe("Vault Tests", () => {
     console.log("\n Test: Initialize Vault");
 
     const tx = await vaultProgram.methods
-      .initializeVault()
+      .initializeVault("AtomX Vault Share", "axVLT", "")
       .accounts({
+        config: vaultConfig,
+        mint,
         vault: vaultPda,
+        vaultToken: vaultTokenAccount,
+        shareMint,
         authority: provider.wallet.publicKey,
         swapRouter: swapRouterProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc();
@@ -111,6 +122,9 @@ describe("Vault Tests", () => {
     const vaultAccount = await vaultProgram.account.vault.fetch(vaultPda);
     
     assert.ok(vaultAccount.authority.equals(provider.wallet.publicKey));
+    assert.ok(vaultAccount.mint.equals(mint));
+    assert.ok(vaultAccount.tokenAccount.equals(vaultTokenAccount));
+    assert.ok(vaultAccount.shareMint.equals(shareMint));
     assert.ok(vaultAccount.swapRouter.equals(swapRouterProgram.programId));
     assert.equal(vaultAccount.totalShares.toNumber(), 0);
     
@@ -118,6 +132,98 @@ describe("Vault Tests", () => {
     console.log("   Authority:", vaultAccount.authority.toString());
     console.log("   Swap Router:", vaultAccount.swapRouter.toString());
     console.log("   Total Shares:", vaultAccount.totalShares.toNumber());
+
+    userShareToken = await createAccount(
+      provider.connection,
+      provider.wallet.payer,
+      shareMint,
+      provider.wallet.publicKey,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+  });
+
+  it("Initializes an independent vault for another mint", async () => {
+    console.log("\n Test: Second vault for another mint");
+
+    const otherMint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      provider.wallet.publicKey,
+      null,
+      6
+    );
+    const [otherVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), otherMint.toBuffer()],
+      vaultProgram.programId
+    );
+
+    await vaultProgram.methods
+      .initializeVault("Other Vault Share", "axOTH", "")
+      .accounts({
+        config: vaultConfig,
+        mint: otherMint,
+        vault: otherVaultPda,
+        vaultToken: anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("vault_token"), otherVaultPda.toBuffer()],
+          vaultProgram.programId
+        )[0],
+        shareMint: shareMintFor(otherVaultPda),
+        authority: provider.wallet.publicKey,
+        swapRouter: swapRouterProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const otherVault = await vaultProgram.account.vault.fetch(otherVaultPda);
+    assert.ok(!otherVaultPda.equals(vaultPda));
+    assert.ok(otherVault.mint.equals(otherMint));
+    assert.ok(!otherVault.shareMint.equals(shareMint));
+    assert.equal(otherVault.totalShares.toNumber(), 0);
+    console.log(" Second vault initialized:", otherVaultPda.toString());
+  });
+
+  it("Rejects a deposit into a vault token account of another mint", async () => {
+    console.log("\n Test: Deposit with mismatched mint (should fail)");
+
+    const otherMint = await createMint(
+      provider.connection,
+      provider.wallet.payer,
+      provider.wallet.publicKey,
+      null,
+      6
+    );
+    const otherVaultToken = await createAccount(
+      provider.connection,
+      provider.wallet.payer,
+      otherMint,
+      vaultPda,
+      anchor.web3.Keypair.generate()
+    );
+
+    try {
+      await vaultProgram.methods
+        .deposit(new anchor.BN(1_000000), new anchor.BN(0))
+        .accounts({
+          vault: vaultPda,
+          user: provider.wallet.publicKey,
+          userToken: userTokenAccount,
+          vaultToken: otherVaultToken,
+          mint: otherMint,
+          shareMint,
+          userShareToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          shareTokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      console.log(" Correctly failed with error:", error.error.errorMessage);
+      assert.include(error.error.errorMessage, "not the vault's canonical token account");
+    }
   });
 
   it("Deposit into Vault", async () => {
@@ -130,15 +236,17 @@ describe("Vault Tests", () => {
     console.log("User balance before:", userBalanceBefore.amount.toString());
 
     const tx = await vaultProgram.methods
-      .deposit(depositAmount)
+      .deposit(depositAmount, new anchor.BN(0))
       .accounts({
         vault: vaultPda,
-        userPosition: userPositionPda,
         user: provider.wallet.publicKey,
         userToken: userTokenAccount,
         vaultToken: vaultTokenAccount,
+        mint,
+        shareMint,
+        userShareToken,
         tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: anchor.web3.SystemProgram.programId,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
       })
       .rpc();
 
@@ -157,12 +265,11 @@ describe("Vault Tests", () => {
     );
     assert.equal(vaultBalance.amount.toString(), depositAmount.toString());
 
-    // Vérifier la position de l'user
-    const userPosition = await vaultProgram.account.userPosition.fetch(userPositionPda);
-    console.log("User shares:", userPosition.shares.toNumber());
+    // Vérifier les shares de l'user
+    const userShares = await shareBalance();
+    console.log("User shares:", userShares.toString());
     
-    assert.equal(userPosition.shares.toNumber(), depositAmount.toNumber());
-    assert.ok(userPosition.owner.equals(provider.wallet.publicKey));
+    assert.equal(userShares.toString(), depositAmount.toString());
 
     // Vérifier le vault
     const vault = await vaultProgram.account.vault.fetch(vaultPda);
@@ -180,35 +287,38 @@ describe("Vault Tests", () => {
 
     // État avant
     const vaultBefore = await vaultProgram.account.vault.fetch(vaultPda);
-    const userPositionBefore = await vaultProgram.account.userPosition.fetch(userPositionPda);
+    const sharesBefore = await shareBalance();
     
-    console.log("Shares before:", userPositionBefore.shares.toNumber());
+    console.log("Shares before:", sharesBefore.toString());
     console.log("Total shares before:", vaultBefore.totalShares.toNumber());
 
     const tx = await vaultProgram.methods
-      .deposit(depositAmount)
+      .deposit(depositAmount, new anchor.BN(0))
       .accounts({
         vault: vaultPda,
-        userPosition: userPositionPda,
         user: provider.wallet.publicKey,
         userToken: userTokenAccount,
         vaultToken: vaultTokenAccount,
+        mint,
+        shareMint,
+        userShareToken,
         tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: anchor.web3.SystemProgram.programId,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
       })
       .rpc();
 
     console.log("Transaction signature:", tx);
 
     // État après
-    const userPositionAfter = await vaultProgram.account.userPosition.fetch(userPositionPda);
+    const sharesAfter = await shareBalance();
     const vaultAfter = await vaultProgram.account.vault.fetch(vaultPda);
     
-    console.log("Shares after:", userPositionAfter.shares.toNumber());
+    console.log("Shares after:", sharesAfter.toString());
     console.log("Total shares after:", vaultAfter.totalShares.toNumber());
 
     // Les shares augmentent
-    assert.ok(userPositionAfter.shares.gt(userPositionBefore.shares));
+    assert.ok(sharesAfter > sharesBefore);
+    assert.equal(vaultAfter.totalShares.toString(), sharesAfter.toString());
 
     console.log(" Second deposit successful");
   });
@@ -216,8 +326,8 @@ describe("Vault Tests", () => {
   it("Withdraw from Vault", async () => {
     console.log("\n Test: Withdraw from Vault");
 
-    const userPosition = await vaultProgram.account.userPosition.fetch(userPositionPda);
-    const sharesToWithdraw = userPosition.shares.div(new anchor.BN(2)); // Retire 50%
+    const sharesBefore = await shareBalance();
+    const sharesToWithdraw = new anchor.BN((sharesBefore / 2n).toString()); // Retire 50%
 
     console.log("Withdrawing shares:", sharesToWithdraw.toNumber());
 
@@ -226,15 +336,17 @@ describe("Vault Tests", () => {
     console.log("User balance before:", userBalanceBefore.amount.toString());
 
     const tx = await vaultProgram.methods
-      .withdraw(sharesToWithdraw)
+      .withdraw(sharesToWithdraw, new anchor.BN(0))
       .accounts({
         vault: vaultPda,
-        userPosition: userPositionPda,
         user: provider.wallet.publicKey,
+        shareMint,
+        userShareToken,
         vaultToken: vaultTokenAccount,
+        mint,
         userToken: userTokenAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
-        owner: provider.wallet.publicKey,
+        shareTokenProgram: TOKEN_2022_PROGRAM_ID,
       })
       .rpc();
 
@@ -248,10 +360,10 @@ describe("Vault Tests", () => {
     assert.ok(BigInt(userBalanceAfter.amount.toString()) > BigInt(userBalanceBefore.amount.toString()));
 
     // Vérifier la position
-    const userPositionAfter = await vaultProgram.account.userPosition.fetch(userPositionPda);
-    console.log("Shares remaining:", userPositionAfter.shares.toNumber());
+    const sharesAfter = await shareBalance();
+    console.log("Shares remaining:", sharesAfter.toString());
     
-    assert.ok(userPositionAfter.shares.lt(userPosition.shares));
+    assert.equal((sharesBefore - sharesAfter).toString(), sharesToWithdraw.toString());
 
     console.log(" Withdrawal successful");
   });
@@ -259,20 +371,21 @@ describe("Vault Tests", () => {
   it("Cannot withdraw more shares than owned", async () => {
     console.log("\n Test: Withdraw too many shares (should fail)");
 
-    const userPosition = await vaultProgram.account.userPosition.fetch(userPositionPda);
-    const tooManyShares = userPosition.shares.add(new anchor.BN(1000000));
+    const tooManyShares = new anchor.BN(((await shareBalance()) + 1_000_000n).toString());
 
     try {
       await vaultProgram.methods
-        .withdraw(tooManyShares)
+        .withdraw(tooManyShares, new anchor.BN(0))
         .accounts({
           vault: vaultPda,
-          userPosition: userPositionPda,
           user: provider.wallet.publicKey,
+          shareMint,
+          userShareToken,
           vaultToken: vaultTokenAccount,
+          mint,
           userToken: userTokenAccount,
           tokenProgram: TOKEN_PROGRAM_ID,
-          owner: provider.wallet.publicKey,
+          shareTokenProgram: TOKEN_2022_PROGRAM_ID,
         })
         .rpc();
 
@@ -280,7 +393,37 @@ describe("Vault Tests", () => {
       assert.fail("Should have thrown an error");
     } catch (error: any) {
       console.log(" Correctly failed with error:", error.error.errorMessage);
-      assert.include(error.error.errorMessage, "InsufficientShares");
+      assert.include(error.error.errorMessage, "Insufficient shares to withdraw");
+    }
+  });
+
+  it("Rejects an arbitrage landing after its deadline", async () => {
+    console.log("\n Test: Expired arbitrage (should fail)");
+
+    try {
+      await vaultProgram.methods
+        .executeArbitrage(Buffer.from([]), new anchor.BN(1), null, new anchor.BN(1))
+        .accounts({
+          vault: vaultPda,
+          vaultToken: vaultTokenAccount,
+          mint,
+          executor: provider.wallet.publicKey,
+          executorToken: userTokenAccount,
+          protocolFeeToken: userTokenAccount,
+          swapRouterProgram: swapRouterProgram.programId,
+          routerState: anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("router_state")],
+            swapRouterProgram.programId
+          )[0],
+          jupiterProgram: new anchor.web3.PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      console.log(" Correctly failed with error:", error.error.errorMessage);
+      assert.include(error.error.errorMessage, "Arbitrage expired");
     }
   });
-});
\ No newline at end of file
+});